@@ -0,0 +1,78 @@
+// a command line version of the calculator, for trying out engine changes without an emulator
+// run it with cargo run -p mathengine --features std --bin repl
+
+use std::io::{self, BufRead, Write};
+
+use mathengine::core::{Calc, CalcEngine, OutputLine};
+use mathengine::format::{format_complex, format_number, format_uncertain, DisplayMode};
+use mathengine::token::Token;
+
+// as wide as an answer on the gba's screen
+const WIDTH: usize = 29;
+
+fn format_answer(answer: &Token) -> String {
+    let text = match answer {
+        Token::Number(n) => format_number(*n, DisplayMode::Float, WIDTH),
+        Token::Uncertain(value, uncertainty) => format_uncertain(*value, *uncertainty, DisplayMode::Float, WIDTH),
+        Token::List(list) => {
+            let numbers: Vec<String> = list
+                .iter()
+                .map(|n| String::from_utf8_lossy(&format_number(*n, DisplayMode::Float, WIDTH)).into_owned())
+                .collect();
+            format!("{{{}}}", numbers.join(",")).into_bytes()
+        }
+        other => format!("{:?}", other).into_bytes(),
+    };
+    String::from_utf8_lossy(&text).into_owned()
+}
+
+fn main() {
+    let mut engine = CalcEngine::new();
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    loop {
+        print!("> ");
+        let _ = stdout.flush();
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => (),
+        }
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "quit" | "exit" => break,
+            "rad" => engine.use_radians = true,
+            "deg" => engine.use_radians = false,
+            _ => {
+                let result = engine.eval(line.as_bytes().to_vec());
+
+                // extra lines like the roots from polyroots, then any table from ode or amort
+                for output_line in engine.output.drain(..) {
+                    match output_line {
+                        OutputLine::Value(label, value) => println!("{}{}", String::from_utf8_lossy(&label), format_answer(&value)),
+                        OutputLine::Complex(label, re, im) => println!(
+                            "{}{}",
+                            String::from_utf8_lossy(&label),
+                            String::from_utf8_lossy(&format_complex(re, im, DisplayMode::Float, WIDTH))
+                        ),
+                        OutputLine::Text(text) => println!("{}", String::from_utf8_lossy(&text)),
+                    }
+                }
+                if let Some(table) = engine.table.take() {
+                    println!("{}", table.columns.join("\t"));
+                    for row in table.rows() {
+                        let row: Vec<String> = row.iter().map(|n| n.to_string()).collect();
+                        println!("{}", row.join("\t"));
+                    }
+                }
+
+                match result {
+                    Ok(answer) => println!("{}", format_answer(&answer)),
+                    Err(message) => println!("{}", message),
+                }
+            }
+        }
+    }
+}
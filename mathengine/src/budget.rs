@@ -0,0 +1,66 @@
+use alloc::boxed::Box;
+
+use super::core::CalcEngine;
+
+// how many steps one evaluation can take before it's stopped, so a huge sum can't freeze the calculator
+pub const MAX_STEPS: usize = 1000000;
+// how deep user functions can call each other, the gba only has a small stack in iwram
+pub const MAX_DEPTH: usize = 16;
+// how many steps go by between each time poll gets called
+const POLL_INTERVAL: usize = 256;
+
+// keeps track of how much work the current evaluation has done
+pub struct Budget {
+    steps: usize,
+    since_poll: usize,
+    pub depth: usize,
+    pub max_steps: usize,
+    // called every so often while evaluating, so the ui can keep the sound going and check for input
+    // the evaluation is cancelled if it returns false
+    pub poll: Option<Box<dyn FnMut() -> bool>>,
+}
+
+impl Default for Budget {
+    fn default() -> Budget {
+        Budget::new()
+    }
+}
+
+impl Budget {
+    pub fn new() -> Budget {
+        Budget {
+            steps: 0,
+            since_poll: 0,
+            depth: 0,
+            max_steps: MAX_STEPS,
+            poll: None,
+        }
+    }
+
+    // called when the user starts something, like entering a line or running a program
+    pub fn start(&mut self) {
+        self.steps = 0;
+        self.depth = 0;
+    }
+}
+
+impl CalcEngine {
+    // counts one step of work, and gives the ui a turn every so often
+    pub fn step(&mut self) -> Result<(), &'static str> {
+        let budget = &mut self.budget;
+        budget.steps += 1;
+        if budget.steps > budget.max_steps {
+            return Err("ran out of steps");
+        }
+        budget.since_poll += 1;
+        if budget.since_poll >= POLL_INTERVAL {
+            budget.since_poll = 0;
+            if let Some(poll) = &mut budget.poll {
+                if !poll() {
+                    return Err("cancelled");
+                }
+            }
+        }
+        Ok(())
+    }
+}
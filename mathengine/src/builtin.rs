@@ -0,0 +1,285 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use super::{
+    real::Real,
+    symbol::{Interner, Symbol},
+};
+
+// every built in function the engine starts with, in the order the catalog shows them
+pub const BUILTINS: [Builtin; 28] = [
+    unary(b"sin", Unary::Sin, Domain::Any, Angle::Takes, "x", "sine"),
+    unary(b"cos", Unary::Cos, Domain::Any, Angle::Takes, "x", "cosine"),
+    unary(b"tan", Unary::Tan, Domain::Any, Angle::Takes, "x", "tangent"),
+    unary(b"asin", Unary::Asin, Domain::UnitInterval, Angle::Gives, "x", "inverse sine"),
+    unary(b"acos", Unary::Acos, Domain::UnitInterval, Angle::Gives, "x", "inverse cosine"),
+    unary(b"atan", Unary::Atan, Domain::Any, Angle::Gives, "x", "inverse tangent"),
+    unary(b"sqrt", Unary::Sqrt, Domain::NonNegative, Angle::None, "x", "square root"),
+    unary(b"log", Unary::Log, Domain::Positive, Angle::None, "x", "log base 10"),
+    unary(b"ln", Unary::Ln, Domain::Positive, Angle::None, "x", "natural log"),
+    other(b"sum", BuiltinKind::Bound(Bound::Sum), Arity::Fixed(4), "i,a,b,expr", "expr added up for i=a..b"),
+    other(b"prod", BuiltinKind::Bound(Bound::Prod), Arity::Fixed(4), "i,a,b,expr", "expr multiplied for i=a..b"),
+    other(b"seq", BuiltinKind::Bound(Bound::Seq), Arity::Fixed(4), "i,a,b,expr", "list of expr for i=a..b"),
+    other(b"ode", BuiltinKind::Ode(Ode::Rk4), Arity::Fixed(5), "f,t0,y0,t1,n", "y at t1 for y'=f(t,y), RK4"),
+    other(b"ode45", BuiltinKind::Ode(Ode::Rk45), Arity::Optional(4, 5), "f,t0,y0,t1,tol", "y at t1 for y'=f(t,y), RK45"),
+    other(b"rand", BuiltinKind::Random(Random::Rand), Arity::Fixed(0), "", "random from 0 to 1"),
+    other(b"randint", BuiltinKind::Random(Random::Randint), Arity::Fixed(2), "a,b", "random whole number a to b"),
+    other(b"randnorm", BuiltinKind::Random(Random::Randnorm), Arity::Fixed(2), "mu,sigma", "normally distributed random"),
+    other(b"randsample", BuiltinKind::Random(Random::Randsample), Arity::Variadic(1), "list", "random pick from the list"),
+    other(b"seed", BuiltinKind::Random(Random::Seed), Arity::Fixed(1), "n", "makes random repeatable"),
+    other(b"polyroots", BuiltinKind::Polyroots, Arity::Variadic(2), "a,b,..", "roots of ax^n+bx^(n-1)+.."),
+    other(b"tvmn", BuiltinKind::Finance(Finance::TvmN), Arity::Optional(4, 6), "I%,PV,PMT,FV", "number of payments"),
+    other(b"tvmi", BuiltinKind::Finance(Finance::TvmI), Arity::Optional(4, 6), "N,PV,PMT,FV", "yearly interest %"),
+    other(b"tvmpv", BuiltinKind::Finance(Finance::TvmPv), Arity::Optional(4, 6), "N,I%,PMT,FV", "present value"),
+    other(b"tvmpmt", BuiltinKind::Finance(Finance::TvmPmt), Arity::Optional(4, 6), "N,I%,PV,FV", "payment each period"),
+    other(b"tvmfv", BuiltinKind::Finance(Finance::TvmFv), Arity::Optional(4, 6), "N,I%,PV,PMT", "future value"),
+    other(b"npv", BuiltinKind::Finance(Finance::Npv), Arity::Variadic(2), "rate,cf0,..", "net present value"),
+    other(b"irr", BuiltinKind::Finance(Finance::Irr), Arity::Variadic(2), "cf0,cf1,..", "internal rate of return"),
+    other(b"amort", BuiltinKind::Finance(Finance::Amort), Arity::Optional(4, 6), "N,I%,PV,PMT", "payment schedule table"),
+];
+
+// everything the engine needs to know about a built in function, adding one to BUILTINS is all it takes
+#[derive(Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static [u8],
+    pub kind: BuiltinKind,
+    pub arity: Arity,
+    // checked against every argument that is a number
+    pub domain: Domain,
+    pub angle: Angle,
+    // what the arguments are called in the catalog, eg. "i,a,b,expr"
+    pub args: &'static str,
+    pub help: &'static str,
+}
+
+const fn unary(name: &'static [u8], function: Unary, domain: Domain, angle: Angle, args: &'static str, help: &'static str) -> Builtin {
+    Builtin {
+        name,
+        kind: BuiltinKind::Unary(function),
+        arity: Arity::Fixed(1),
+        domain,
+        angle,
+        args,
+        help,
+    }
+}
+
+const fn other(name: &'static [u8], kind: BuiltinKind, arity: Arity, args: &'static str, help: &'static str) -> Builtin {
+    Builtin {
+        name,
+        kind,
+        arity,
+        domain: Domain::Any,
+        angle: Angle::None,
+        args,
+        help,
+    }
+}
+
+impl Builtin {
+    // how it's typed, eg. sum(i,a,b,expr)
+    pub fn usage(&self) -> Vec<u8> {
+        let mut text = self.name.to_vec();
+        text.push(b'(');
+        text.extend_from_slice(self.args.as_bytes());
+        text.push(b')');
+        text
+    }
+}
+
+// which part of the engine solves a built in function
+#[derive(Clone, Copy, Debug)]
+pub enum BuiltinKind {
+    // a function of one number, which also works on +/- values and in the vm
+    Unary(Unary),
+    // sum, prod and seq, which have an index variable
+    Bound(Bound),
+    // ode and ode45, which take the name of a function
+    Ode(Ode),
+    // rand and the others that use the engine's rng
+    Random(Random),
+    Polyroots,
+    // the tvm functions, npv, irr and amort
+    Finance(Finance),
+    // any other function of numbers, for ones that don't need anything from the engine
+    Native(fn(&[f64]) -> Result<f64, &'static str>),
+}
+
+// how many arguments a built in function takes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arity {
+    Fixed(usize),
+    // at least the first and at most the second
+    Optional(usize, usize),
+    // at least this many
+    Variadic(usize),
+}
+
+impl Arity {
+    pub fn accepts(self, count: usize) -> bool {
+        match self {
+            Arity::Fixed(n) => count == n,
+            Arity::Optional(min, max) => count >= min && count <= max,
+            Arity::Variadic(min) => count >= min,
+        }
+    }
+}
+
+// the numbers a built in function takes, anything else is an error instead of NaN
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Domain {
+    Any,
+    NonNegative,
+    Positive,
+    // from -1 to 1
+    UnitInterval,
+}
+
+impl Domain {
+    // works in either number type, so FAST mode doesn't have to convert to check
+    // the negated comparisons are on purpose, so NaN fails every domain
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    pub fn check<R: Real>(self, x: R) -> Result<(), &'static str> {
+        match self {
+            Domain::NonNegative if !(x >= R::ZERO) => Err("argument must be at least 0"),
+            Domain::Positive if !(x > R::ZERO) => Err("argument must be more than 0"),
+            Domain::UnitInterval if !(x >= -R::ONE && x <= R::ONE) => Err("argument must be from -1 to 1"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Sum,
+    Prod,
+    Seq,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ode {
+    // fixed steps
+    Rk4,
+    // adaptive steps
+    Rk45,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Random {
+    Rand,
+    Randint,
+    Randnorm,
+    Randsample,
+    Seed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Finance {
+    TvmN,
+    TvmI,
+    TvmPv,
+    TvmPmt,
+    TvmFv,
+    Npv,
+    Irr,
+    Amort,
+}
+
+// whether the function works with angles, which are in degrees or radians depending on the mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Angle {
+    None,
+    // the argument is an angle, like sin
+    Takes,
+    // the answer is an angle, like asin
+    Gives,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unary {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sqrt,
+    Log,
+    Ln,
+}
+
+impl Unary {
+    // everything here is in radians, apply_unary converts from and to degrees
+    fn apply<R: Real>(self, x: R) -> R {
+        match self {
+            Unary::Sin => x.sin(),
+            Unary::Cos => x.cos(),
+            Unary::Tan => x.tan(),
+            Unary::Asin => x.asin(),
+            Unary::Acos => x.acos(),
+            Unary::Atan => x.atan(),
+            Unary::Sqrt => x.sqrt(),
+            Unary::Log => x.log10(),
+            Unary::Ln => x.ln(),
+        }
+    }
+}
+
+// a function of one number with its domain checked, angle_mode is what angles are multiplied by to get radians
+pub fn apply_unary<R: Real>(function: Unary, domain: Domain, angle: Angle, x: R, angle_mode: R) -> Result<R, &'static str> {
+    domain.check(x)?;
+    Ok(match angle {
+        Angle::None => function.apply(x),
+        Angle::Takes => function.apply(x * angle_mode),
+        Angle::Gives => function.apply(x) / angle_mode,
+    })
+}
+
+// the built in functions the engine knows about, looked up by the symbol of their name
+pub struct Registry {
+    builtins: Vec<Builtin>,
+    indexes: BTreeMap<Symbol, usize>,
+}
+
+impl Registry {
+    pub fn new(symbols: &mut Interner) -> Registry {
+        let mut registry = Registry {
+            builtins: Vec::new(),
+            indexes: BTreeMap::new(),
+        };
+        for builtin in BUILTINS {
+            registry.register(symbols, builtin);
+        }
+        registry
+    }
+
+    // adds a built in function, or replaces the one that has the same name
+    pub fn register(&mut self, symbols: &mut Interner, builtin: Builtin) {
+        let symbol = symbols.intern(builtin.name);
+        match self.indexes.get(&symbol) {
+            Some(index) => self.builtins[*index] = builtin,
+            None => {
+                self.indexes.insert(symbol, self.builtins.len());
+                self.builtins.push(builtin);
+            }
+        }
+    }
+
+    pub fn get(&self, symbol: Symbol) -> Option<&Builtin> {
+        self.indexes.get(&symbol).map(|index| &self.builtins[*index])
+    }
+
+    // the kind of function symbol is, None if it isn't built in
+    pub fn kind(&self, symbol: Symbol) -> Option<BuiltinKind> {
+        self.get(symbol).map(|builtin| builtin.kind)
+    }
+
+    // the symbols of every built in function's name
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.indexes.keys().copied()
+    }
+
+    // every built in function, in the order they were registered
+    pub fn all(&self) -> &[Builtin] {
+        &self.builtins
+    }
+}
@@ -0,0 +1,271 @@
+use alloc::vec::Vec;
+
+use crate::token::Token::{self, *};
+
+use super::{
+    builtin::{apply_unary, Angle, BuiltinKind, Domain, Registry, Unary},
+    real::{operate, Constant, Fixed, NumberMode, Real},
+    solve::factorial,
+};
+
+// the most values the vm can have on its stack at once, anything deeper is left to the solver
+const MAX_STACK: usize = 32;
+
+// one step of a compiled expression, which works on a stack of numbers like an rpn calculator
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Push(Constant),
+    // pushes the argument with this index
+    Arg(usize),
+    // pops b then a, and pushes a op b, the token is the operator
+    Binary(Token),
+    Negate,
+    Factorial,
+    // sin, sqrt and the other built in functions of one number
+    Builtin(Unary, Domain, Angle),
+}
+
+// turns a solved-ready token list into ops, or None if it has something only the solver handles,
+// like variables, lists, +/- values, percents or calls to functions the user made
+// anything that only uses constants is worked out here, so 2*pi*x is just one multiply when it runs
+pub fn compile(tokens: &[Token], builtins: &Registry) -> Option<Vec<Op>> {
+    let mut compiler = Compiler {
+        tokens,
+        builtins,
+        index: 0,
+        code: Vec::new(),
+        depth: 0,
+        max_depth: 0,
+    };
+    compiler.comparison()?;
+    if compiler.index != tokens.len() || compiler.max_depth > MAX_STACK {
+        return None;
+    }
+    Some(compiler.code)
+}
+
+// a recursive descent parser with the same precedence as solve_rec, each level calls the one that binds tighter
+struct Compiler<'a> {
+    tokens: &'a [Token],
+    builtins: &'a Registry,
+    index: usize,
+    code: Vec<Op>,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl Compiler<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn comparison(&mut self) -> Option<()> {
+        self.additive()?;
+        while let Some(operator @ (Less | Greater | LessEqual | GreaterEqual | Equal | NotEqual)) = self.peek() {
+            let operator = operator.clone();
+            self.index += 1;
+            self.additive()?;
+            self.binary(operator);
+        }
+        Some(())
+    }
+
+    fn additive(&mut self) -> Option<()> {
+        self.multiplicative()?;
+        while let Some(operator @ (Addition | Subtraction)) = self.peek() {
+            let operator = operator.clone();
+            self.index += 1;
+            self.multiplicative()?;
+            self.binary(operator);
+        }
+        Some(())
+    }
+
+    fn multiplicative(&mut self) -> Option<()> {
+        self.unary()?;
+        while let Some(operator @ (Multiplication | Division | Modulation)) = self.peek() {
+            let operator = operator.clone();
+            self.index += 1;
+            self.unary()?;
+            self.binary(operator);
+        }
+        Some(())
+    }
+
+    // unary '-' binds looser than '^', so -2^2 is -4
+    fn unary(&mut self) -> Option<()> {
+        if self.peek() == Some(&Negation) {
+            self.index += 1;
+            self.unary()?;
+            self.negate();
+            return Some(());
+        }
+        self.power()
+    }
+
+    // '^' goes left to right like the solver, and a sign right after it belongs to the exponent, eg. 2^-3
+    fn power(&mut self) -> Option<()> {
+        self.postfix()?;
+        while self.peek() == Some(&Exponentation) {
+            self.index += 1;
+            let mut negations = 0;
+            while self.peek() == Some(&Negation) {
+                self.index += 1;
+                negations += 1;
+            }
+            self.postfix()?;
+            if negations % 2 == 1 {
+                self.negate();
+            }
+            self.binary(Exponentation);
+        }
+        Some(())
+    }
+
+    fn postfix(&mut self) -> Option<()> {
+        self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Factorial) => {
+                    self.index += 1;
+                    match self.code.last_mut() {
+                        Some(Op::Push(n)) if factorial(n.precise).is_ok() => *n = Constant::new(factorial(n.precise).unwrap_or(n.precise)),
+                        _ => self.code.push(Op::Factorial),
+                    }
+                }
+                // a percent depends on what is around it, so the solver has to do it
+                Some(Percent) => return None,
+                _ => return Some(()),
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Option<()> {
+        match self.peek()?.clone() {
+            Number(n) => {
+                self.index += 1;
+                self.push(Op::Push(Constant::new(n)));
+            }
+            FunctionArg(index) => {
+                self.index += 1;
+                self.push(Op::Arg(index));
+            }
+            LeftBracket => {
+                self.index += 1;
+                self.comparison()?;
+                if self.peek() != Some(&RightBracket) {
+                    return None;
+                }
+                self.index += 1;
+            }
+            FunctionName(name) => {
+                let builtin = self.builtins.get(name)?;
+                let op = match builtin.kind {
+                    BuiltinKind::Unary(function) => Op::Builtin(function, builtin.domain, builtin.angle),
+                    _ => return None,
+                };
+                if self.tokens.get(self.index + 1) != Some(&LeftBracket) {
+                    return None;
+                }
+                self.index += 2;
+                self.comparison()?;
+                if self.peek() != Some(&RightBracket) {
+                    return None;
+                }
+                self.index += 1;
+                self.code.push(op);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn push(&mut self, op: Op) {
+        self.code.push(op);
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+    }
+
+    fn negate(&mut self) {
+        match self.code.last_mut() {
+            Some(Op::Push(n)) => {
+                *n = Constant {
+                    precise: -n.precise,
+                    fast: -n.fast,
+                }
+            }
+            _ => self.code.push(Op::Negate),
+        }
+    }
+
+    // folds the operator into one number if both sides are already numbers, in both number types
+    fn binary(&mut self, operator: Token) {
+        self.depth -= 1;
+        let len = self.code.len();
+        if len >= 2 {
+            if let (Op::Push(a), Op::Push(b)) = (&self.code[len - 2], &self.code[len - 1]) {
+                let answer = Constant {
+                    precise: evaluate_binary(&operator, a.precise, b.precise),
+                    fast: evaluate_binary(&operator, a.fast, b.fast),
+                };
+                self.code.truncate(len - 2);
+                self.code.push(Op::Push(answer));
+                return;
+            }
+        }
+        self.code.push(Op::Binary(operator));
+    }
+}
+
+fn evaluate_binary<R: Real>(operator: &Token, a: R, b: R) -> R {
+    let compare = |result: bool| if result { R::ONE } else { R::ZERO };
+    match operator {
+        Less => compare(a < b),
+        Greater => compare(a > b),
+        LessEqual => compare(a <= b),
+        GreaterEqual => compare(a >= b),
+        Equal => compare(a == b),
+        NotEqual => compare(a != b),
+        _ => operate(operator, a, b),
+    }
+}
+
+// runs compiled ops with these arguments, in the number type the mode picks
+// the arguments are the tokens they were solved to, so calling doesn't have to copy them into a list of numbers
+pub fn run(code: &[Op], args: &[Token], angle_mode: f64, mode: NumberMode) -> Result<f64, &'static str> {
+    match mode {
+        NumberMode::Precise => run_with::<f64>(code, args, angle_mode),
+        NumberMode::Fast => run_with::<Fixed>(code, args, angle_mode),
+    }
+}
+
+// the stack is a fixed size array, so running doesn't allocate anything
+fn run_with<R: Real>(code: &[Op], args: &[Token], angle_mode: f64) -> Result<f64, &'static str> {
+    let mut stack = [R::from_f64(0.0); MAX_STACK];
+    let mut top = 0;
+    let angle_mode = R::from_f64(angle_mode);
+    for op in code {
+        match op {
+            Op::Push(n) => {
+                stack[top] = R::from_constant(*n);
+                top += 1;
+            }
+            Op::Arg(index) => {
+                stack[top] = match args.get(*index) {
+                    Some(Number(n)) => R::from_f64(*n),
+                    Some(_) => return Err("function arguments must be numbers"),
+                    None => return Err("incorrect argument count"),
+                };
+                top += 1;
+            }
+            Op::Binary(operator) => {
+                top -= 1;
+                stack[top - 1] = evaluate_binary(operator, stack[top - 1], stack[top]);
+            }
+            Op::Negate => stack[top - 1] = -stack[top - 1],
+            Op::Factorial => stack[top - 1] = R::from_f64(factorial(stack[top - 1].to_f64())?),
+            Op::Builtin(function, domain, angle) => stack[top - 1] = apply_unary(*function, *domain, *angle, stack[top - 1], angle_mode)?,
+        }
+    }
+    Ok(stack[0].to_f64())
+}
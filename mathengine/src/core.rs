@@ -0,0 +1,361 @@
+use crate::token::{tokenize, Token, Token::*};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::f64::consts::{E, PI, TAU};
+
+use super::budget::Budget;
+use super::builtin::{Builtin, Registry};
+use super::formula::FormulaHandling;
+use super::manage::parse_command;
+use super::math_function::{Function, FunctionHandling};
+use super::memory::MAX_TABLE_ROWS;
+use super::program::{Program, ProgramRun};
+use super::random::Rng;
+use super::real::NumberMode;
+use super::uncertainty::UncertaintyMode;
+use super::solve::Solver;
+use super::symbol::{Interner, Symbol};
+use super::token::TokenHandling;
+use super::validate::{is_valid_lhs_function, validate_token_list};
+
+// how many previous answers are kept, ans (or ans1) is the newest and ans9 is the oldest
+pub const ANSWER_COUNT: usize = 9;
+
+pub struct CalcEngine {
+    pub prev_answers: Vec<Token>,
+    // every variable and function name, the maps below are keyed by their symbols
+    pub symbols: Interner,
+    // the built in functions, which user functions can't be named after
+    pub builtins: Registry,
+    pub variables: BTreeMap<Symbol, Token>,
+    pub functions: BTreeMap<Symbol, Function>,
+    // variables made with ':=', their value in variables is recomputed when a variable they use changes
+    pub formulas: BTreeMap<Symbol, Vec<Token>>,
+    // variables made with let, which are cleared after every line
+    pub locals: BTreeMap<Symbol, Token>,
+    // programs are only looked up by the ui, so they keep their names
+    pub programs: BTreeMap<Vec<u8>, Program>,
+    // the program that is waiting on a prompt, if there is one
+    pub running: Option<ProgramRun>,
+    // extra lines from disp or polyroots, for the ui to take and put in the history
+    pub output: Vec<OutputLine>,
+    // a table of numbers from something like ode, for the ui to show on its own screen
+    pub table: Option<ResultTable>,
+    // where rand and the other random functions get their numbers
+    pub rng: Rng,
+    // how much work the current evaluation has done, and how to cancel it
+    pub budget: Budget,
+    pub use_radians: bool,
+    pub uncertainty_mode: UncertaintyMode,
+    pub number_mode: NumberMode,
+    // a running total of roughly how many bytes the saved things take, see memory_usage
+    pub(crate) saved_bytes: usize,
+}
+
+impl Default for CalcEngine {
+    fn default() -> CalcEngine {
+        CalcEngine::new()
+    }
+}
+
+impl CalcEngine {
+    pub fn new() -> CalcEngine {
+        let mut symbols = Interner::new();
+        let builtins = Registry::new(&mut symbols);
+        let mut calc_engine = CalcEngine {
+            prev_answers: Vec::new(),
+            symbols,
+            builtins,
+            variables: BTreeMap::<Symbol, Token>::new(),
+            functions: BTreeMap::<Symbol, Function>::new(),
+            formulas: BTreeMap::<Symbol, Vec<Token>>::new(),
+            locals: BTreeMap::<Symbol, Token>::new(),
+            programs: BTreeMap::<Vec<u8>, Program>::new(),
+            running: None,
+            output: Vec::new(),
+            table: None,
+            rng: Rng::new(),
+            budget: Budget::new(),
+            use_radians: true,
+            uncertainty_mode: UncertaintyMode::Gaussian,
+            number_mode: NumberMode::Precise,
+            saved_bytes: 0,
+        };
+        calc_engine.prev_answers.resize(ANSWER_COUNT, Number(0.0));
+        calc_engine.add_constants();
+        calc_engine.recount_memory();
+
+        calc_engine
+    }
+
+    pub fn add_constants(&mut self) {
+        for (name, value) in [(b"pi".as_slice(), PI), (b"e", E), (b"tau", TAU)] {
+            let symbol = self.symbols.intern(name);
+            self.insert_variable(symbol, Number(value));
+        }
+    }
+
+    // the previous answer a name like ans or ans3 refers to
+    pub fn get_answer(&self, name: Symbol) -> Option<&Token> {
+        match self.symbols.name(name) {
+            b"ans" => self.prev_answers.first(),
+            [b'a', b'n', b's', digit @ b'1'..=b'9'] => self.prev_answers.get((digit - b'1') as usize),
+            _ => None,
+        }
+    }
+
+    // a plain value replaces any formula, and updates formulas that use it
+    pub fn set_variable(&mut self, name: Symbol, value: Token) -> Result<(), &'static str> {
+        self.check_variable(name, &value)?;
+        self.insert_variable(name, value);
+        self.remove_formula(name);
+        self.update_formulas(name);
+        Ok(())
+    }
+
+    // adds a built in function, eg. a Native one that isn't in BUILTINS
+    pub fn register_builtin(&mut self, builtin: Builtin) {
+        self.builtins.register(&mut self.symbols, builtin);
+    }
+
+    pub fn tokenize(&mut self, input: &[u8]) -> Result<Vec<Token>, &'static str> {
+        tokenize(input, &mut self.symbols)
+    }
+}
+
+pub trait Calc {
+    fn eval(&mut self, input: Vec<u8>) -> Result<Token, &'static str>;
+}
+
+// a line of output, with a label like x1= in front
+pub enum OutputLine {
+    Value(Vec<u8>, Token),
+    Complex(Vec<u8>, f64, f64),
+    // a line that is shown as it is, like the definitions from list
+    Text(Vec<u8>),
+}
+
+// rows of numbers with a name for each column, stored one row after another
+pub struct ResultTable {
+    pub columns: Vec<&'static str>,
+    pub values: Vec<f64>,
+    // how many rows were pushed for every one that is kept
+    pub stride: usize,
+    pushed: usize,
+    // the newest row is always kept, even when it isn't one of every stride rows
+    tail: bool,
+}
+
+impl ResultTable {
+    pub fn new(columns: &[&'static str]) -> ResultTable {
+        ResultTable {
+            columns: columns.to_vec(),
+            values: Vec::new(),
+            stride: 1,
+            pushed: 0,
+            tail: false,
+        }
+    }
+
+    pub fn rows(&self) -> core::slice::Chunks<'_, f64> {
+        self.values.chunks(self.columns.len())
+    }
+
+    // adds a row, once there are MAX_TABLE_ROWS every other one is dropped so it can't run out of memory
+    pub fn push(&mut self, row: &[f64]) {
+        let width = self.columns.len();
+        if self.tail {
+            self.values.truncate(self.values.len() - width);
+        }
+        if self.pushed.is_multiple_of(self.stride) && self.values.len() == MAX_TABLE_ROWS * width {
+            for i in 0..MAX_TABLE_ROWS / 2 {
+                self.values.copy_within(2 * i * width..(2 * i + 1) * width, i * width);
+            }
+            self.values.truncate(MAX_TABLE_ROWS / 2 * width);
+            self.stride *= 2;
+        }
+        self.tail = !self.pushed.is_multiple_of(self.stride);
+        self.values.extend_from_slice(row);
+        self.pushed += 1;
+    }
+}
+
+// what running a single statement did
+pub enum Statement {
+    // a number, or a list from seq
+    Answer(Token),
+    // assignments and the like, which only have a message for the user
+    Message(&'static str),
+}
+
+impl Calc for CalcEngine {
+    fn eval(&mut self, input: Vec<u8>) -> Result<Token, &'static str> {
+        self.budget.start();
+        // a program waiting on a prompt still has symbols for its names
+        if self.running.is_none() {
+            self.free_names();
+        }
+        self.recount_memory();
+        match self.run_line(&input) {
+            Ok(Statement::Answer(answer)) => Ok(answer),
+            Ok(Statement::Message(message)) => Err(message),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl CalcEngine {
+    // runs every statement in a line, the result is the one from the last statement
+    pub fn run_line(&mut self, input: &[u8]) -> Result<Statement, &'static str> {
+        // lines like del x manage variables and functions instead of being worked out
+        if let Some(command) = parse_command(input, &mut self.symbols) {
+            return self.run_command(command?);
+        }
+
+        // turn string input into a list of tokens
+        let tokens = self.tokenize(input)?;
+
+        // make sure token list is a valid equation or assignment
+        if let Some(e) = validate_token_list(&tokens) {
+            return Err(e);
+        }
+
+        // run each statement in order, stopping at the first error
+        // locals made with let only last until the end of the line
+        self.locals.clear();
+        let mut result = Err("missing expression");
+        for statement in tokens.split(|t| t == &Semicolon) {
+            if statement.is_empty() {
+                continue;
+            }
+            result = self.eval_statement(statement.to_vec());
+            if result.is_err() {
+                break;
+            }
+        }
+        self.locals.clear();
+        result
+    }
+
+    // written with explicit returns and matches for every case, from before the engine had its own crate
+    #[allow(clippy::needless_return, clippy::single_match, clippy::needless_late_init, clippy::question_mark)]
+    fn eval_statement(&mut self, mut tokens: Vec<Token>) -> Result<Statement, &'static str> {
+        // let makes a local variable instead of a normal one
+        let is_local = tokens[0] == Let;
+        if is_local {
+            tokens.remove(0);
+        }
+
+        // if creating/reassigning a variable/function (expression contains a '=' or ':=')
+        if let Some(equals_index) = tokens.iter().position(|t| t == &Assignment || t == &Definition) {
+
+            // split the expression into the parts before and after the '='
+            let mut lhs = tokens[..equals_index].to_vec();
+            let mut rhs = tokens[equals_index + 1..].to_vec();
+            // ':=' leaves variables in the rhs alone, so they get looked up every time it is used
+            let late_binding = tokens[equals_index] == Definition;
+
+            // check if lhs is a variable, or a function
+            if lhs.len() == 1 {
+                if let Variable(name) = lhs[0] {
+                    if self.get_answer(name).is_some() {
+                        return Err("ans cannot be reassigned");
+                    }
+                    if is_local {
+                        if late_binding {
+                            return Err("let needs a '='");
+                        }
+                        // lhs is a local variable, it only lasts until the end of the line
+                        match self.resolve_variables(&mut rhs) {
+                            Some(e) => {
+                                return Err(e);
+                            }
+                            _ => ()
+                        }
+                        let value;
+                        match self.solve(rhs) {
+                            Ok(answer) => value = answer[0].clone(),
+                            Err(e) => return Err(e)
+                        }
+                        self.locals.insert(name, value);
+                        return Ok(Statement::Message("assigned local variable"));
+                    }
+                    if late_binding {
+                        // lhs is a formula variable, it keeps the rhs and recomputes it when needed
+                        match self.create_formula(name, rhs) {
+                            Ok(()) => return Ok(Statement::Message("assigned formula to variable")),
+                            // still saved, it gets a value once the variables it uses exist
+                            Err("Variable does not exist") => return Ok(Statement::Message("formula waiting on variables")),
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    // lhs is a variable, assign value to new variable
+                    // resolve variables on the rhs
+                    match self.resolve_variables(&mut rhs) {
+                        Some(e) => {
+                            return Err(e);
+                        }
+                        _ => ()
+                    }
+                    let value;
+                    match self.solve(rhs) {
+                        Ok(answer) => value = answer[0].clone(),
+                        Err(e) => return Err(e)
+                    }
+
+                    self.set_variable(name, value)?;
+                    return Ok(Statement::Message("assigned value to variable"));
+                } else {
+                    return Err("lhs must be variable or function");
+                }
+            } else {
+                match is_valid_lhs_function(&lhs, &self.builtins) {
+                    None => {
+                        // lhs is a function, assign value to new function
+                        match self.create_function(&mut lhs, &mut rhs, late_binding) {
+                            Ok(func) => {
+                                self.check_function(&func)?;
+                                self.insert_function(func);
+                                return Ok(Statement::Message("created function"));
+                            }
+                            Err(e) => {
+                                return Err(e);
+                            }
+                        }
+                    }
+                    Some(e) => {
+                        return Err(e);
+                    }
+                }
+            }
+		// otherwise just solve it
+        } else {
+
+            // resolve variables on the right side
+            match self.resolve_variables(&mut tokens) {
+                Some(e) => {
+                    return Err(e);
+                }
+                _ => ()
+            }
+        
+            let answer;
+            match self.solve(tokens) {
+                Ok(a) => answer = a[0].clone(),
+                Err(e) => return Err(e)
+            }
+
+            if let Number(_) | List(_) | Uncertain(..) = answer {
+                self.push_answer(answer.clone());
+                return Ok(Statement::Answer(answer));
+            } else {
+				return Err("Couldn't solve equation");
+            }
+        }
+    }
+}
+
+pub struct TokenStringResult {
+    pub length: usize,
+    pub operator: Token,
+}
@@ -0,0 +1,243 @@
+use alloc::vec::Vec;
+
+use libm::{fabs, floor, log, pow};
+
+use crate::token::Token::{self, *};
+
+use super::{
+    builtin::Finance,
+    core::{CalcEngine, OutputLine, ResultTable},
+    math_function::MAX_TERMS,
+};
+
+// the time value of money fields, in the order the tvm screen asks for them
+pub const TVM_FIELDS: [&str; 7] = ["N", "I%", "PV", "PMT", "FV", "P/Y", "C/Y"];
+
+// payments happen at the end of each period, and money you get is positive while money you pay is negative
+pub trait FinanceHandling {
+    fn solve_tvm(&mut self, values: &[Option<f64>]) -> Result<(), &'static str>;
+    // tvmn(I%, PV, PMT, FV), tvmi(N, PV, PMT, FV), tvmpv(N, I%, PMT, FV), tvmpmt(N, I%, PV, FV), tvmfv(N, I%, PV, PMT)
+    // npv(rate, cf0, cf1, ..), irr(cf0, cf1, ..) and amort(N, I%, PV, PMT) for the payment schedule
+    fn solve_finance(&mut self, function: Finance, args: &[f64]) -> Result<Token, &'static str>;
+}
+
+impl FinanceHandling for CalcEngine {
+    // exactly one of N, I%, PV, PMT and FV is left out and gets solved, P/Y and C/Y are 1 if they are left out
+    fn solve_tvm(&mut self, values: &[Option<f64>]) -> Result<(), &'static str> {
+        if values.len() != TVM_FIELDS.len() {
+            return Err("tvm needs N, I%, PV, PMT, FV, P/Y and C/Y");
+        }
+        let unknowns: Vec<usize> = (0..5).filter(|i| values[*i].is_none()).collect();
+        if unknowns.len() != 1 {
+            return Err("leave exactly one of N to FV blank");
+        }
+        let unknown = unknowns[0];
+        let known = |i: usize| values[i].unwrap_or(0.0);
+        let py = values[5].unwrap_or(1.0);
+        let cy = values[6].unwrap_or(1.0);
+
+        let answer = match unknown {
+            0 => tvm_n(known(1), known(2), known(3), known(4), py, cy)?,
+            1 => tvm_i(known(0), known(2), known(3), known(4), py, cy)?,
+            2 => tvm_pv(known(0), known(1), known(3), known(4), py, cy)?,
+            3 => tvm_pmt(known(0), known(1), known(2), known(4), py, cy)?,
+            _ => tvm_fv(known(0), known(1), known(2), known(3), py, cy)?,
+        };
+        if !answer.is_finite() {
+            return Err("no answer for these values");
+        }
+
+        let mut label = TVM_FIELDS[unknown].as_bytes().to_vec();
+        label.push(b'=');
+        self.output.push(OutputLine::Value(label, Number(answer)));
+        Ok(())
+    }
+
+    fn solve_finance(&mut self, function: Finance, args: &[f64]) -> Result<Token, &'static str> {
+        // P/Y and C/Y can go on the end of the tvm functions and amort
+        let rates = |count: usize| -> Result<(f64, f64), &'static str> {
+            match args.len() - count {
+                0 => Ok((1.0, 1.0)),
+                1 => Ok((args[count], args[count])),
+                2 => Ok((args[count], args[count + 1])),
+                _ => Err("incorrect argument count"),
+            }
+        };
+
+        let answer = match function {
+            Finance::TvmN => {
+                let (py, cy) = rates(4)?;
+                tvm_n(args[0], args[1], args[2], args[3], py, cy)?
+            }
+            Finance::TvmI => {
+                let (py, cy) = rates(4)?;
+                tvm_i(args[0], args[1], args[2], args[3], py, cy)?
+            }
+            Finance::TvmPv => {
+                let (py, cy) = rates(4)?;
+                tvm_pv(args[0], args[1], args[2], args[3], py, cy)?
+            }
+            Finance::TvmPmt => {
+                let (py, cy) = rates(4)?;
+                tvm_pmt(args[0], args[1], args[2], args[3], py, cy)?
+            }
+            Finance::TvmFv => {
+                let (py, cy) = rates(4)?;
+                tvm_fv(args[0], args[1], args[2], args[3], py, cy)?
+            }
+            Finance::Npv => npv(args[0] / 100.0, &args[1..]),
+            Finance::Irr => irr(args)?,
+            Finance::Amort => {
+                let (py, cy) = rates(4)?;
+                let (balance, table) = amortize(args[0], args[1], args[2], args[3], py, cy)?;
+                self.table = Some(table);
+                balance
+            }
+        };
+        if !answer.is_finite() {
+            return Err("no answer for these values");
+        }
+        Ok(Number(answer))
+    }
+}
+
+// the interest rate for each payment, from the yearly I% that is compounded C/Y times a year
+fn period_rate(interest: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    if py <= 0.0 || cy <= 0.0 {
+        return Err("P/Y and C/Y must be more than 0");
+    }
+    Ok(pow(1.0 + interest / (100.0 * cy), cy / py) - 1.0)
+}
+
+// what is left over when everything is moved to the end, which is 0 when the values fit together
+fn tvm_balance(n: f64, i: f64, pv: f64, pmt: f64, fv: f64) -> f64 {
+    if fabs(i) < 1e-12 {
+        return pv + pmt * n + fv;
+    }
+    let growth = pow(1.0 + i, n);
+    pv * growth + pmt * (growth - 1.0) / i + fv
+}
+
+fn tvm_n(interest: f64, pv: f64, pmt: f64, fv: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    let i = period_rate(interest, py, cy)?;
+    if i == 0.0 {
+        return Ok(-(pv + fv) / pmt);
+    }
+    let growth = (pmt / i - fv) / (pmt / i + pv);
+    if growth.is_nan() || growth <= 0.0 {
+        return Err("no N for these values");
+    }
+    Ok(log(growth) / log(1.0 + i))
+}
+
+fn tvm_i(n: f64, pv: f64, pmt: f64, fv: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    // check P/Y and C/Y before searching
+    period_rate(0.0, py, cy)?;
+    let i = find_rate(|i| tvm_balance(n, i, pv, pmt, fv), fabs(pv) + fabs(pmt * n) + fabs(fv))?;
+    Ok(100.0 * cy * (pow(1.0 + i, py / cy) - 1.0))
+}
+
+fn tvm_pv(n: f64, interest: f64, pmt: f64, fv: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    let i = period_rate(interest, py, cy)?;
+    if i == 0.0 {
+        return Ok(-(fv + pmt * n));
+    }
+    let growth = pow(1.0 + i, n);
+    Ok(-(fv + pmt * (growth - 1.0) / i) / growth)
+}
+
+fn tvm_pmt(n: f64, interest: f64, pv: f64, fv: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    let i = period_rate(interest, py, cy)?;
+    if i == 0.0 {
+        return Ok(-(pv + fv) / n);
+    }
+    let growth = pow(1.0 + i, n);
+    Ok(-(pv * growth + fv) * i / (growth - 1.0))
+}
+
+fn tvm_fv(n: f64, interest: f64, pv: f64, pmt: f64, py: f64, cy: f64) -> Result<f64, &'static str> {
+    let i = period_rate(interest, py, cy)?;
+    Ok(-tvm_balance(n, i, pv, pmt, 0.0))
+}
+
+// net present value of cash flows one period apart, the first one is now
+fn npv(rate: f64, cash_flows: &[f64]) -> f64 {
+    let mut value = 0.0;
+    for (k, cash_flow) in cash_flows.iter().enumerate() {
+        value += cash_flow / pow(1.0 + rate, k as f64);
+    }
+    value
+}
+
+// the rate in percent that makes the npv 0
+fn irr(cash_flows: &[f64]) -> Result<f64, &'static str> {
+    if !cash_flows.iter().any(|c| *c > 0.0) || !cash_flows.iter().any(|c| *c < 0.0) {
+        return Err("irr needs money going both ways");
+    }
+    let size = cash_flows.iter().fold(0.0, |a, c| a + fabs(*c));
+    Ok(100.0 * find_rate(|rate| npv(rate, cash_flows), size)?)
+}
+
+// the payment schedule, each row is the period, interest, principal and balance after it
+fn amortize(n: f64, interest: f64, pv: f64, pmt: f64, py: f64, cy: f64) -> Result<(f64, ResultTable), &'static str> {
+    if n < 1.0 || n > MAX_TERMS || n != floor(n) {
+        return Err("N must be a whole number from 1 to 10000");
+    }
+    let i = period_rate(interest, py, cy)?;
+    let mut balance = pv;
+    let mut table = ResultTable::new(&["N", "INT", "PRN", "BAL"]);
+    for period in 1..=n as usize {
+        let paid_interest = -balance * i;
+        let principal = pmt - paid_interest;
+        balance += principal;
+        table.push(&[period as f64, paid_interest, principal, balance]);
+    }
+    Ok((balance, table))
+}
+
+// rates to look between for a change of sign, rates of -100% or less don't mean anything
+const RATE_GRID: [f64; 19] = [
+    -0.9, -0.5, -0.2, -0.1, -0.05, -0.01, -0.001, 0.0, 0.001, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 10.0,
+];
+
+// finds a rate above -100% where f is 0, by bisecting the change of sign closest to 0%
+// size is roughly how big the numbers going into f are, to know when f is close enough to 0
+fn find_rate<F>(f: F, size: f64) -> Result<f64, &'static str>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut bracket: Option<(f64, f64)> = None;
+    for pair in RATE_GRID.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let (fa, fb) = (f(a), f(b));
+        if fa.is_finite() && fb.is_finite() && (fa <= 0.0) != (fb <= 0.0) {
+            let closer = match bracket {
+                Some((low, high)) => fabs(a + b) < fabs(low + high),
+                None => true,
+            };
+            if closer {
+                bracket = Some((a, b));
+            }
+        }
+    }
+    let (mut low, mut high) = match bracket {
+        Some(bracket) => bracket,
+        None => return Err("couldn't find the rate"),
+    };
+
+    let low_is_negative = f(low) <= 0.0;
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if (f(middle) <= 0.0) == low_is_negative {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    let rate = (low + high) / 2.0;
+    if fabs(f(rate)) <= 1e-6 * (1.0 + size) {
+        Ok(rate + 0.0)
+    } else {
+        Err("couldn't find the rate")
+    }
+}
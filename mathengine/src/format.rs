@@ -0,0 +1,174 @@
+use alloc::{format, string::String, string::ToString, vec::Vec};
+
+use libm::{fabs, floor, log10, pow};
+
+// SI prefixes for engineering notation, from 10^-24 up to 10^24
+const SI_PREFIXES: [&str; 17] = [
+    "y", "z", "a", "f", "p", "n", "u", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+];
+
+// how many significant digits FLOAT shows, so 0.1+0.2 comes out as 0.3
+const FLOAT_DIGITS: usize = 10;
+
+// how answers get turned into text
+// the number in FIX, SCI and ENG is how many digits go after the decimal point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DisplayMode {
+    Float,
+    Fix(u8),
+    Sci(u8),
+    Eng(u8),
+}
+
+impl DisplayMode {
+    pub fn digits(&self) -> u8 {
+        match self {
+            DisplayMode::Float => 4,
+            DisplayMode::Fix(d) | DisplayMode::Sci(d) | DisplayMode::Eng(d) => *d,
+        }
+    }
+
+    pub fn with_digits(&self, digits: u8) -> DisplayMode {
+        match self {
+            DisplayMode::Float => DisplayMode::Float,
+            DisplayMode::Fix(_) => DisplayMode::Fix(digits),
+            DisplayMode::Sci(_) => DisplayMode::Sci(digits),
+            DisplayMode::Eng(_) => DisplayMode::Eng(digits),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            DisplayMode::Float => "FLOAT".to_string(),
+            DisplayMode::Fix(d) => format!("FIX {}", d),
+            DisplayMode::Sci(d) => format!("SCI {}", d),
+            DisplayMode::Eng(d) => format!("ENG {}", d),
+        }
+    }
+}
+
+// turns a number into text that is at most width characters long
+pub fn format_number(n: f64, mode: DisplayMode, width: usize) -> Vec<u8> {
+    if !n.is_finite() {
+        return n.to_string().into_bytes();
+    }
+
+    let text = match mode {
+        DisplayMode::Float => {
+            let magnitude = fabs(n);
+            if n == 0.0 || (1e-4..1e10).contains(&magnitude) {
+                fit_decimal(round_significant(n, FLOAT_DIGITS), width)
+            } else {
+                trim_exponent_zeros(fit_scientific(n, FLOAT_DIGITS - 1, width))
+            }
+        }
+        DisplayMode::Fix(digits) => {
+            let text = format!("{:.*}", digits as usize, n);
+            if fabs(n) < 1e10 && text.len() <= width {
+                text
+            } else {
+                fit_scientific(n, digits as usize, width)
+            }
+        }
+        DisplayMode::Sci(digits) => fit_scientific(n, digits as usize, width),
+        DisplayMode::Eng(digits) => {
+            let text = engineering(n, digits as usize);
+            if text.len() <= width {
+                text
+            } else {
+                fit_scientific(n, digits as usize, width)
+            }
+        }
+    };
+
+    text.into_bytes()
+}
+
+// eg. 1.5+2i, the real and imaginary parts share the width
+pub fn format_complex(re: f64, im: f64, mode: DisplayMode, width: usize) -> Vec<u8> {
+    if im == 0.0 {
+        return format_number(re, mode, width);
+    }
+    // room for the sign and the i
+    let re_width = width.saturating_sub(2) / 2;
+    let im_width = width.saturating_sub(2) - re_width;
+    let mut text = format_number(re, mode, re_width);
+    text.push(if im < 0.0 { b'-' } else { b'+' });
+    text.extend(format_number(fabs(im), mode, im_width));
+    text.push(b'i');
+    text
+}
+
+// eg. 3.2+/-0.1, the value and the uncertainty share the width
+pub fn format_uncertain(value: f64, uncertainty: f64, mode: DisplayMode, width: usize) -> Vec<u8> {
+    // room for the +/-
+    let value_width = width.saturating_sub(3) / 2;
+    let uncertainty_width = width.saturating_sub(3) - value_width;
+    let mut text = format_number(value, mode, value_width);
+    text.extend_from_slice(b"+/-");
+    text.extend(format_number(uncertainty, mode, uncertainty_width));
+    text
+}
+
+// the shortest text that gives back n, rounded off if it doesn't fit
+fn fit_decimal(n: f64, width: usize) -> String {
+    let text = n.to_string();
+    if text.len() <= width {
+        return text;
+    }
+    let whole_digits = format!("{:.0}", n).len();
+    let decimals = width.saturating_sub(whole_digits + 1);
+    trim_decimal_zeros(format!("{:.*}", decimals, n))
+}
+
+// eg. 6.02E23, with fewer digits after the point if it doesn't fit
+fn fit_scientific(n: f64, digits: usize, width: usize) -> String {
+    let mut digits = digits;
+    loop {
+        let text = format!("{:.*E}", digits, n);
+        if text.len() <= width || digits == 0 {
+            return text;
+        }
+        digits -= 1;
+    }
+}
+
+// like scientific, but the exponent is a multiple of 3 and shown as an SI prefix
+fn engineering(n: f64, digits: usize) -> String {
+    if n == 0.0 {
+        return format!("{:.*}", digits, n);
+    }
+    let mut exponent = (floor(log10(fabs(n)) / 3.0) * 3.0) as i32;
+    let mut mantissa = n / pow(10.0, exponent as f64);
+    // rounding can push the mantissa up to 1000, eg. 999.99 with 1 digit
+    if fabs(format!("{:.*}", digits, mantissa).parse::<f64>().unwrap_or(mantissa)) >= 1000.0 {
+        exponent += 3;
+        mantissa /= 1000.0;
+    }
+    let prefix_index = exponent / 3 + 8;
+    if (0..SI_PREFIXES.len() as i32).contains(&prefix_index) {
+        format!("{:.*}{}", digits, mantissa, SI_PREFIXES[prefix_index as usize])
+    } else {
+        format!("{:.*}E{}", digits, mantissa, exponent)
+    }
+}
+
+fn round_significant(n: f64, digits: usize) -> f64 {
+    format!("{:.*E}", digits - 1, n).parse::<f64>().unwrap_or(n)
+}
+
+fn trim_decimal_zeros(text: String) -> String {
+    if !text.contains('.') {
+        return text;
+    }
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn trim_exponent_zeros(text: String) -> String {
+    match text.split_once('E') {
+        Some((mantissa, exponent)) => {
+            format!("{}E{}", trim_decimal_zeros(mantissa.to_string()), exponent)
+        }
+        None => text,
+    }
+}
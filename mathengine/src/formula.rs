@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+
+use crate::token::Token::{self, *};
+
+use super::{core::CalcEngine, solve::Solver, symbol::Symbol, token::TokenHandling};
+
+pub trait FormulaHandling {
+    fn create_formula(&mut self, name: Symbol, expr: Vec<Token>) -> Result<(), &'static str>;
+    fn update_formulas(&mut self, changed: Symbol);
+    fn recompute_formula(&mut self, name: Symbol) -> Result<(), &'static str>;
+}
+
+impl FormulaHandling for CalcEngine {
+    // makes name a formula variable, which gets recomputed whenever a variable in expr changes
+    fn create_formula(&mut self, name: Symbol, expr: Vec<Token>) -> Result<(), &'static str> {
+        if self.formula_depends_on(&expr, name, &mut Vec::new()) {
+            return Err("formula depends on itself");
+        }
+        self.check_formula(name, &expr)?;
+
+        self.insert_formula(name, expr);
+        let result = self.recompute_formula(name);
+        self.update_formulas(name);
+        result
+    }
+
+    // recomputes every formula that depends on changed, dependencies before dependents
+    fn update_formulas(&mut self, changed: Symbol) {
+        let mut visited: Vec<Symbol> = Vec::new();
+        let mut order: Vec<Symbol> = Vec::new();
+        self.find_dependents(changed, &mut visited, &mut order);
+
+        for name in order.iter().rev() {
+            // a formula that can't be computed yet just has no value until it can be
+            let _ = self.recompute_formula(*name);
+        }
+    }
+
+    fn recompute_formula(&mut self, name: Symbol) -> Result<(), &'static str> {
+        let mut expr = match self.formulas.get(&name) {
+            Some(expr) => expr.clone(),
+            None => return Ok(()),
+        };
+
+        self.remove_variable(name);
+        if let Some(e) = self.resolve_variables(&mut expr) {
+            return Err(e);
+        }
+        match self.solve(expr) {
+            Ok(answer) => {
+                self.insert_variable(name, answer[0].clone());
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl CalcEngine {
+    // checks if expr uses target, either directly or through other formulas
+    fn formula_depends_on(&self, expr: &[Token], target: Symbol, visited: &mut Vec<Symbol>) -> bool {
+        for name in dependencies(expr) {
+            if name == target {
+                return true;
+            }
+            if visited.contains(&name) {
+                continue;
+            }
+            visited.push(name);
+            if let Some(formula) = self.formulas.get(&name) {
+                if self.formula_depends_on(formula, target, visited) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // depth first search over every formula using name, order ends up with dependents before dependencies
+    fn find_dependents(&self, name: Symbol, visited: &mut Vec<Symbol>, order: &mut Vec<Symbol>) {
+        for (formula_name, expr) in self.formulas.iter() {
+            if visited.contains(formula_name) || !dependencies(expr).contains(&name) {
+                continue;
+            }
+            visited.push(*formula_name);
+            self.find_dependents(*formula_name, visited, order);
+            order.push(*formula_name);
+        }
+    }
+}
+
+// every variable name used in expr
+fn dependencies(expr: &[Token]) -> Vec<Symbol> {
+    let mut names: Vec<Symbol> = Vec::new();
+    for token in expr {
+        if let Variable(name) = token {
+            if !names.contains(name) {
+                names.push(*name);
+            }
+        }
+    }
+    names
+}
@@ -0,0 +1,28 @@
+// the calculator's math, which doesn't depend on the gba so it can be tested on a computer
+#![no_std]
+
+extern crate alloc;
+
+pub mod budget;
+pub mod builtin;
+pub mod bytecode;
+pub mod core;
+pub mod error;
+pub mod finance;
+pub mod format;
+pub mod formula;
+pub mod linear;
+pub mod manage;
+pub mod math_function;
+pub mod memory;
+pub mod ode;
+pub mod polynomial;
+pub mod print;
+pub mod program;
+pub mod random;
+pub mod real;
+pub mod solve;
+pub mod symbol;
+pub mod token;
+pub mod uncertainty;
+pub mod validate;
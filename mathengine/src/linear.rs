@@ -0,0 +1,90 @@
+use alloc::{format, vec::Vec};
+
+use libm::fabs;
+
+use crate::token::Token::*;
+
+use super::core::{CalcEngine, OutputLine};
+
+// how many unknowns the linear solver takes
+pub const MIN_UNKNOWNS: usize = 2;
+pub const MAX_UNKNOWNS: usize = 6;
+
+pub trait LinearHandling {
+    fn solve_system(&mut self, size: usize, values: &[f64]) -> Result<(), &'static str>;
+}
+
+impl LinearHandling for CalcEngine {
+    // values are the rows of the system one after the other, each is its coefficients and then its right hand side
+    // the answers go in x1 up to xN, and in the output
+    fn solve_system(&mut self, size: usize, values: &[f64]) -> Result<(), &'static str> {
+        if !(MIN_UNKNOWNS..=MAX_UNKNOWNS).contains(&size) || values.len() != size * (size + 1) {
+            return Err("system is the wrong size");
+        }
+        let rows: Vec<Vec<f64>> = values.chunks(size + 1).map(|row| row.to_vec()).collect();
+        let solution = gaussian_elimination(rows)?;
+
+        for (i, x) in solution.iter().enumerate() {
+            let name = format!("x{}", i + 1).into_bytes();
+            let symbol = self.symbols.intern(&name);
+            self.set_variable(symbol, Number(*x))?;
+            let mut label = name;
+            label.push(b'=');
+            self.output.push(OutputLine::Value(label, Number(*x)));
+        }
+        Ok(())
+    }
+}
+
+// solves a square system given as rows of coefficients with the right hand side on the end
+fn gaussian_elimination(mut rows: Vec<Vec<f64>>) -> Result<Vec<f64>, &'static str> {
+    let n = rows.len();
+    if rows.iter().flatten().any(|x| !x.is_finite()) {
+        return Err("system must be finite");
+    }
+
+    // anything this small next to the biggest number is a rounding error, and counts as 0
+    let biggest = rows.iter().flatten().fold(0.0, |a: f64, x| a.max(fabs(*x)));
+    let tolerance = 1e-12 * biggest;
+
+    // forward elimination, each column uses the row with the biggest value in it as the pivot
+    let mut pivot_row = 0;
+    for column in 0..n {
+        let best = (pivot_row..n)
+            .max_by(|a, b| fabs(rows[*a][column]).total_cmp(&fabs(rows[*b][column])))
+            .unwrap_or(pivot_row);
+        if pivot_row >= n || fabs(rows[best][column]) <= tolerance {
+            // nothing to pivot on, so this unknown isn't pinned down by the equations
+            continue;
+        }
+        rows.swap(pivot_row, best);
+
+        for row in pivot_row + 1..n {
+            let factor = rows[row][column] / rows[pivot_row][column];
+            for c in column..=n {
+                rows[row][c] -= factor * rows[pivot_row][c];
+            }
+        }
+        pivot_row += 1;
+    }
+
+    // rows without a pivot are 0 = something, which is fine if the something is 0 too
+    if pivot_row < n {
+        if rows[pivot_row..].iter().any(|row| fabs(row[n]) > tolerance) {
+            return Err("system has no solution");
+        }
+        return Err("system has infinitely many solutions");
+    }
+
+    // back substitution
+    let mut solution: Vec<f64> = Vec::new();
+    solution.resize(n, 0.0);
+    for row in (0..n).rev() {
+        let mut sum = rows[row][n];
+        for c in row + 1..n {
+            sum -= rows[row][c] * solution[c];
+        }
+        solution[row] = sum / rows[row][row] + 0.0;
+    }
+    Ok(solution)
+}
@@ -0,0 +1,229 @@
+use alloc::{format, vec::Vec};
+
+use crate::token::Token::{self, FunctionName, Number, Variable};
+
+use super::{
+    core::{CalcEngine, OutputLine, Statement, ANSWER_COUNT},
+    formula::FormulaHandling,
+    memory::{HEAP_SIZE, MAX_FUNCTIONS, MAX_MEMORY, MAX_VARIABLES},
+    symbol::{Interner, Symbol},
+};
+
+// something the user has saved, for list and the memory screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Saved {
+    Variable(Symbol),
+    Function(Symbol),
+}
+
+// a line that manages variables and functions instead of being worked out, eg. del x
+pub enum Command {
+    List,
+    Delete(Symbol),
+    ClearVariables,
+    ClearFunctions,
+    // clears everything, programs and previous answers too
+    Reset,
+    Rename(Symbol, Symbol),
+    // how much of the memory for saved things is used
+    Memory,
+}
+
+// the first word of every command and every program keyword, these can't be used as names
+pub const RESERVED_WORDS: [&[u8]; 13] = [
+    b"list", b"del", b"clear", b"reset", b"rename", b"mem",
+    b"for", b"while", b"if", b"else", b"end", b"disp", b"prompt",
+];
+
+// None if the line isn't a command, so it gets worked out like normal
+pub fn parse_command(input: &[u8], symbols: &mut Interner) -> Option<Result<Command, &'static str>> {
+    let words: Vec<&[u8]> = input.split(|c| *c == b' ').filter(|word| !word.is_empty()).collect();
+    let command = match words[..] {
+        [b"list"] => Ok(Command::List),
+        [b"del", name] => match symbols.lookup(name) {
+            Some(name) => Ok(Command::Delete(name)),
+            None => Err("nothing has that name"),
+        },
+        [b"del", ..] => Err("del needs a name, eg. del x"),
+        [b"clear", b"vars"] => Ok(Command::ClearVariables),
+        [b"clear", b"funcs"] => Ok(Command::ClearFunctions),
+        [b"clear", ..] => Err("clear vars or clear funcs"),
+        [b"reset"] => Ok(Command::Reset),
+        [b"rename", old, new] => match symbols.lookup(old) {
+            Some(_) if !is_name(new) => Err("that isn't a name"),
+            Some(old) => Ok(Command::Rename(old, symbols.intern(new))),
+            None => Err("nothing has that name"),
+        },
+        [b"rename", ..] => Err("rename needs 2 names, eg. rename x y"),
+        [b"mem"] => Ok(Command::Memory),
+        _ => return None,
+    };
+    Some(command)
+}
+
+// a letter, then letters, digits and '_' like the tokenizer reads
+fn is_name(word: &[u8]) -> bool {
+    word.first().is_some_and(|c| c.is_ascii_alphabetic())
+        && word.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'_')
+        && word != b"let"
+        && !RESERVED_WORDS.contains(&word)
+}
+
+impl CalcEngine {
+    pub fn run_command(&mut self, command: Command) -> Result<Statement, &'static str> {
+        match command {
+            Command::List => {
+                let saved = self.saved();
+                for entry in &saved {
+                    let line = self.saved_text(*entry);
+                    self.output.push(OutputLine::Text(line));
+                }
+                if saved.is_empty() {
+                    return Ok(Statement::Message("nothing is saved"));
+                }
+                Ok(Statement::Message("listed variables and functions"))
+            }
+            Command::Delete(name) => {
+                self.delete(name)?;
+                Ok(Statement::Message("deleted"))
+            }
+            Command::ClearVariables => {
+                self.clear_variables();
+                Ok(Statement::Message("cleared variables"))
+            }
+            Command::ClearFunctions => {
+                self.functions.clear();
+                self.recount_memory();
+                Ok(Statement::Message("cleared functions"))
+            }
+            Command::Reset => {
+                self.reset();
+                Ok(Statement::Message("cleared everything"))
+            }
+            Command::Rename(old, new) => {
+                self.rename(old, new)?;
+                Ok(Statement::Message("renamed"))
+            }
+            Command::Memory => {
+                let usage = self.memory_usage();
+                let lines = [
+                    format!("about {}/{} bytes saved", usage.bytes, MAX_MEMORY),
+                    format!("that's {}% of the {}KB heap", usage.bytes * 100 / HEAP_SIZE, HEAP_SIZE / 1024),
+                    format!("variables {}/{}", usage.variables, MAX_VARIABLES),
+                    format!("functions {}/{}", usage.functions, MAX_FUNCTIONS),
+                    format!("programs {}, names {}", usage.programs, usage.names),
+                ];
+                for line in lines {
+                    self.output.push(OutputLine::Text(line.into_bytes()));
+                }
+                Ok(Statement::Message("bytes are an estimate"))
+            }
+        }
+    }
+
+    // every variable, then every function, each in order of their names
+    pub fn saved(&self) -> Vec<Saved> {
+        let mut variables: Vec<Symbol> = self.variables.keys().copied().collect();
+        // a formula waiting on variables doesn't have a value yet
+        variables.extend(self.formulas.keys().filter(|name| !self.variables.contains_key(name)));
+        variables.sort_by(|a, b| self.symbols.name(*a).cmp(self.symbols.name(*b)));
+        let mut functions: Vec<Symbol> = self.functions.keys().copied().collect();
+        functions.sort_by(|a, b| self.symbols.name(*a).cmp(self.symbols.name(*b)));
+
+        let mut saved: Vec<Saved> = variables.into_iter().map(Saved::Variable).collect();
+        saved.extend(functions.into_iter().map(Saved::Function));
+        saved
+    }
+
+    // how a saved variable or function would be typed, eg. x=4 or f(x)=x^2
+    // ones that can't be typed, like lists, just show their name
+    pub fn saved_text(&self, saved: Saved) -> Vec<u8> {
+        let (text, name) = match saved {
+            Saved::Variable(name) => (self.variable_text(name), name),
+            Saved::Function(name) => (self.function_text(name), name),
+        };
+        text.unwrap_or_else(|| self.symbols.name(name).to_vec())
+    }
+
+    // removes the variable or function called name, both if there are both
+    // formulas that used the variable are recomputed, so they have no value until it's back
+    pub fn delete(&mut self, name: Symbol) -> Result<(), &'static str> {
+        let variable = self.variables.remove(&name).is_some();
+        let formula = self.formulas.remove(&name).is_some();
+        let function = self.functions.remove(&name).is_some();
+        if !variable && !formula && !function {
+            return Err("nothing has that name");
+        }
+        if variable || formula {
+            self.update_formulas(name);
+        }
+        self.recount_memory();
+        Ok(())
+    }
+
+    // everything but pi, e and tau, which are put back if they were changed
+    pub fn clear_variables(&mut self) {
+        self.variables.clear();
+        self.formulas.clear();
+        self.add_constants();
+        self.recount_memory();
+    }
+
+    // back to how it was when turned on, the settings are kept
+    pub fn reset(&mut self) {
+        self.clear_variables();
+        self.functions.clear();
+        self.programs.clear();
+        self.running = None;
+        self.output.clear();
+        self.table = None;
+        self.prev_answers.clear();
+        self.prev_answers.resize(ANSWER_COUNT, Number(0.0));
+        self.recount_memory();
+    }
+
+    // gives a variable or function a new name, formulas and functions that used the old name use the new one
+    pub fn rename(&mut self, old: Symbol, new: Symbol) -> Result<(), &'static str> {
+        if self.builtins.get(new).is_some() {
+            return Err("built in functions cannot be reassigned");
+        }
+        if self.get_answer(new).is_some() {
+            return Err("ans cannot be reassigned");
+        }
+        if self.variables.contains_key(&new) || self.formulas.contains_key(&new) || self.functions.contains_key(&new) {
+            return Err("that name is already used");
+        }
+        let mut variable = false;
+        if let Some(value) = self.variables.remove(&old) {
+            self.variables.insert(new, value);
+            variable = true;
+        }
+        if let Some(expr) = self.formulas.remove(&old) {
+            self.formulas.insert(new, expr);
+            variable = true;
+        }
+        let mut function = false;
+        if let Some(mut saved) = self.functions.remove(&old) {
+            saved.name = new;
+            self.functions.insert(new, saved);
+            function = true;
+        }
+        if !variable && !function {
+            return Err("nothing has that name");
+        }
+
+        // formulas and ':=' functions look names up when they run, so they have to use the new one
+        let rename_token = |token: &mut Token| match token {
+            Variable(name) if variable && *name == old => *token = Variable(new),
+            FunctionName(name) if function && *name == old => *token = FunctionName(new),
+            _ => (),
+        };
+        for expr in self.formulas.values_mut() {
+            expr.iter_mut().for_each(rename_token);
+        }
+        for saved in self.functions.values_mut() {
+            saved.func.iter_mut().for_each(rename_token);
+        }
+        Ok(())
+    }
+}
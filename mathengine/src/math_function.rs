@@ -0,0 +1,297 @@
+use core::f64::consts::PI;
+
+use alloc::{format, vec::Vec};
+
+use crate::token::Token::*;
+
+use super::{
+    budget::MAX_DEPTH,
+    builtin::{apply_unary, Bound, Builtin, BuiltinKind},
+    bytecode::{compile, run, Op},
+    core::{CalcEngine, OutputLine},
+    finance::FinanceHandling,
+    memory::MAX_LIST_LENGTH,
+    polynomial::poly_roots,
+    real::{Fixed, NumberMode, Real},
+    solve::{split_arguments, Solver},
+    symbol::Symbol,
+    token::{Token, TokenHandling},
+};
+
+// the most terms sum, prod and seq will go through
+pub const MAX_TERMS: f64 = 10000.0;
+
+#[derive(Clone)]
+pub struct Function {
+    pub name: Symbol,
+    pub arg_count: usize,
+    // what the arguments were called, so func can be shown the way it was typed
+    pub args: Vec<Symbol>,
+    pub func: Vec<Token>,
+    // made with ':=', so variables in func are looked up when it is called
+    pub late_binding: bool,
+    // func compiled for the vm, None if it needs the solver, like functions made with ':='
+    pub code: Option<Vec<Op>>,
+}
+
+pub trait FunctionHandling {
+    fn create_function(&mut self, lhs: &mut Vec<Token>, rhs: &mut Vec<Token>, late_binding: bool) -> Result<Function, &'static str>;
+    fn solve_function(&mut self, name: Symbol, args: &[Token]) -> Result<Token, &'static str>;
+}
+
+impl FunctionHandling for CalcEngine {
+    fn create_function(&mut self, lhs: &mut Vec<Token>, rhs: &mut Vec<Token>, late_binding: bool) -> Result<Function, &'static str> {
+        let mut arg_count: usize = 0;
+        let mut args: Vec<Symbol> = Vec::new();
+        for token in lhs.iter() {
+            if let Variable(arg_name) = token {
+                args.push(*arg_name);
+                // replace cooresponding rhs variable(s) with a function argument index
+                for rhs_token in rhs.iter_mut() {
+                    if let Variable(var_name) = rhs_token {
+                        if arg_name == var_name {
+                            *rhs_token = FunctionArg(arg_count);
+                        }
+                    }
+                }
+
+                arg_count += 1;
+            }
+        }
+
+        let name = match lhs[0] {
+            FunctionName(func_name) => func_name,
+            _ => return Err("function must have a name"),
+        };
+        // late binding functions keep their variables, and look them up when called
+        if !late_binding {
+            match self.resolve_variables(rhs) {
+                None => (),
+                _ => return Err("invalid variables"),
+            }
+        }
+        let code = if late_binding { None } else { compile(rhs, &self.builtins) };
+
+        Ok(Function {
+            name,
+            arg_count,
+            args,
+            func: rhs.to_vec(),
+            late_binding,
+            code,
+        })
+
+    }
+
+    fn solve_function(&mut self, name: Symbol, arg_slice: &[Token]) -> Result<Token, &'static str> {
+        let arg_tokens = split_arguments(arg_slice);
+        let builtin = self.builtins.get(name).copied();
+        // f() has one empty argument
+        let count = if arg_tokens.len() == 1 && arg_tokens[0].is_empty() { 0 } else { arg_tokens.len() };
+
+        if let Some(builtin) = builtin {
+            if !builtin.arity.accepts(count) {
+                return Err("incorrect argument count");
+            }
+            match builtin.kind {
+                // sum, prod and seq solve their last argument once for every value of the index
+                BuiltinKind::Bound(bound) => return self.solve_bound_function(bound, &arg_tokens),
+                // ode and ode45 call the function named in their first argument
+                BuiltinKind::Ode(method) => return self.solve_ode(method, &arg_tokens),
+                // rand() takes no arguments and randsample takes a list, so they solve their own
+                BuiltinKind::Random(function) => return self.solve_random(function, &arg_tokens),
+                _ => (),
+            }
+        }
+
+        let mut values: Vec<Token> = Vec::new();
+        for arg in arg_tokens.into_iter().take(count) {
+            match self.solve(arg)?[0] {
+                Number(n) => values.push(Number(n)),
+                Uncertain(value, uncertainty) => values.push(Uncertain(value, uncertainty)),
+                _ => return Err("function arguments must be numbers"),
+            }
+        }
+        // +/- values only go through the built in functions and ones the user made
+        if let (Some(builtin @ Builtin { kind: BuiltinKind::Unary(_), .. }), [Uncertain(value, uncertainty)]) = (builtin, &values[..]) {
+            return self.solve_uncertain_builtin(builtin, (*value, *uncertainty));
+        }
+        if values.iter().any(|v| matches!(v, Uncertain(..))) {
+            if self.functions.contains_key(&name) {
+                return self.solve_user_function(name, &values);
+            }
+            return Err("+/- can't be used with this function");
+        }
+        let args: Vec<f64> = values
+            .iter()
+            .filter_map(|v| match v {
+                Number(n) => Some(*n),
+                _ => None,
+            })
+            .collect();
+
+        let builtin = match builtin {
+            Some(builtin) => builtin,
+            None => return self.solve_user_function(name, &values),
+        };
+        for x in &args {
+            builtin.domain.check(*x)?;
+        }
+        match builtin.kind {
+            BuiltinKind::Polyroots => self.solve_polyroots(&args),
+            BuiltinKind::Finance(function) => self.solve_finance(function, &args),
+            BuiltinKind::Native(function) => Ok(Number(function(&args)?)),
+            _ => Ok(Number(self.solve_builtin(&builtin, args[0])?)),
+        }
+    }
+}
+
+impl CalcEngine {
+    // what angles are multiplied by to get radians
+    pub fn angle_mode(&self) -> f64 {
+        if self.use_radians {
+            1.0
+        } else {
+            PI / 180.0
+        }
+    }
+
+    // a built in function of one number like sin or sqrt, in whichever number type the engine is set to
+    pub fn solve_builtin(&self, builtin: &Builtin, x: f64) -> Result<f64, &'static str> {
+        let function = match builtin.kind {
+            BuiltinKind::Unary(function) => function,
+            _ => return Err("function does not exist"),
+        };
+        let angle_mode = self.angle_mode();
+        match self.number_mode {
+            NumberMode::Precise => apply_unary(function, builtin.domain, builtin.angle, x, angle_mode),
+            NumberMode::Fast => {
+                apply_unary(function, builtin.domain, builtin.angle, Fixed::from_f64(x), Fixed::from_f64(angle_mode)).map(Fixed::to_f64)
+            }
+        }
+    }
+
+    // the arguments can be numbers or +/- values, which carry on through the function's expression
+    fn solve_user_function(&mut self, name: Symbol, args: &[Token]) -> Result<Token, &'static str> {
+        self.step()?;
+        match self.functions.get(&name) {
+            Some(function) => {
+                if function.arg_count != args.len() {
+                    return Err("incorrect argument count");
+                }
+                // plain numbers can go straight through the vm
+                if let Some(code) = &function.code {
+                    if args.iter().all(|arg| matches!(arg, Number(_))) {
+                        return Ok(Number(run(code, args, self.angle_mode(), self.number_mode)?));
+                    }
+                }
+                let mut expr = function.func.clone();
+                for token in &mut expr {
+                    if let FunctionArg(index) = token {
+                        *token = args[*index].clone();
+                    }
+                }
+                // only functions made with ':=' have variables left to look up
+                if self.resolve_variables(&mut expr).is_some() {
+                    return Err("function uses undefined variable");
+                }
+                // a function that calls itself would otherwise go until the stack runs out
+                if self.budget.depth >= MAX_DEPTH {
+                    return Err("functions call each other too deeply");
+                }
+                self.budget.depth += 1;
+                let answer = self.solve(expr);
+                self.budget.depth -= 1;
+                match answer {
+                    Ok(answer) => Ok(answer[0].clone()),
+                    Err(e) => Err(e),
+                }
+            }
+            None => Err("function does not exist"),
+        }
+    }
+
+    // eg. sum(i, 1, 10, i^2), the index is only a variable inside the call
+    fn solve_bound_function(&mut self, bound: Bound, args: &[Vec<Token>]) -> Result<Token, &'static str> {
+        let index = match &args[0][..] {
+            [Variable(index)] => *index,
+            _ => return Err("index must be a variable"),
+        };
+        let start = self.solve_argument(args[1].clone())?;
+        let end = self.solve_argument(args[2].clone())?;
+        if end - start >= MAX_TERMS {
+            return Err("too many terms");
+        }
+        // a list is kept as an answer, so it has to fit in memory
+        if bound == Bound::Seq && end - start >= MAX_LIST_LENGTH as f64 {
+            return Err("list is too long");
+        }
+
+        // sum and prod add up as they go, only seq keeps every value
+        let mut total = if bound == Bound::Prod { 1.0 } else { 0.0 };
+        let mut values: Vec<f64> = Vec::new();
+        let mut add = |value: f64| match bound {
+            Bound::Sum => total += value,
+            Bound::Prod => total *= value,
+            Bound::Seq => values.push(value),
+        };
+        let mut k = start;
+        // the index becomes the vm's only argument, if the expression can be compiled
+        let body: Vec<Token> = args[3]
+            .iter()
+            .map(|t| match t {
+                Variable(var_name) if *var_name == index => FunctionArg(0),
+                _ => t.clone(),
+            })
+            .collect();
+        if let Some(code) = compile(&body, &self.builtins) {
+            while k <= end {
+                self.step()?;
+                add(run(&code, &[Number(k)], self.angle_mode(), self.number_mode)?);
+                k += 1.0;
+            }
+        }
+        while k <= end {
+            let expr = args[3]
+                .iter()
+                .map(|t| match t {
+                    Variable(var_name) if *var_name == index => Number(k),
+                    _ => t.clone(),
+                })
+                .collect();
+            add(self.solve_argument(expr)?);
+            k += 1.0;
+        }
+
+        match bound {
+            Bound::Seq => Ok(List(values)),
+            _ => Ok(Number(total)),
+        }
+    }
+
+    // every root goes in the output, and the real ones are the answer
+    fn solve_polyroots(&mut self, coefficients: &[f64]) -> Result<Token, &'static str> {
+        let roots = poly_roots(coefficients)?;
+        let mut real_roots: Vec<f64> = Vec::new();
+        for (i, (re, im)) in roots.roots.iter().enumerate() {
+            let label = format!("x{}=", i + 1).into_bytes();
+            if *im == 0.0 {
+                real_roots.push(*re);
+                self.output.push(OutputLine::Value(label, Number(*re)));
+            } else {
+                self.output.push(OutputLine::Complex(label, *re, *im));
+            }
+        }
+        if !roots.settled {
+            self.output.push(OutputLine::Text(b"roots are approximate".to_vec()));
+        }
+        Ok(List(real_roots))
+    }
+
+    fn solve_argument(&mut self, arg: Vec<Token>) -> Result<f64, &'static str> {
+        match self.solve(arg)?[0] {
+            Number(n) => Ok(n),
+            _ => Err("function arguments must be numbers"),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use core::mem::size_of;
+
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use crate::token::Token::{self, *};
+
+use super::{
+    core::{CalcEngine, ANSWER_COUNT},
+    math_function::Function,
+    symbol::Symbol,
+};
+
+// the gba has 256KB of ram for the heap, and running out of it crashes the calculator,
+// so what the user can save is limited to well under that, leaving the rest for working things out
+// the heap is agb's, which can't say how much of it is used, so mem compares the estimate to this
+pub const HEAP_SIZE: usize = 256 * 1024;
+pub const MAX_MEMORY: usize = 64 * 1024;
+pub const MAX_VARIABLES: usize = 100;
+pub const MAX_FUNCTIONS: usize = 50;
+// how many tokens the body of a function or formula can have
+pub const MAX_DEFINITION_LENGTH: usize = 256;
+// how many numbers a list can have, since every answer and variable could be one
+pub const MAX_LIST_LENGTH: usize = 1000;
+// how many rows a table from something like ode keeps, longer ones only keep every few rows
+pub const MAX_TABLE_ROWS: usize = 500;
+
+// roughly how many bytes everything the user has saved takes up
+pub struct MemoryUsage {
+    pub variables: usize,
+    pub functions: usize,
+    pub programs: usize,
+    // every name the engine knows, built in functions too
+    pub names: usize,
+    // an estimate from the sizes of the tokens and names, the allocator uses some more on top
+    pub bytes: usize,
+}
+
+fn token_size(token: &Token) -> usize {
+    match token {
+        List(values) => size_of::<Token>() + values.len() * size_of::<f64>(),
+        _ => size_of::<Token>(),
+    }
+}
+
+fn tokens_size(tokens: &[Token]) -> usize {
+    tokens.iter().map(token_size).sum()
+}
+
+fn symbols_in(tokens: &[Token]) -> impl Iterator<Item = Symbol> + '_ {
+    tokens.iter().filter_map(|token| match token {
+        UnresolvedString(name) | Variable(name) | FunctionName(name) => Some(*name),
+        _ => None,
+    })
+}
+
+fn function_size(function: &Function) -> usize {
+    let code = match &function.code {
+        Some(code) => core::mem::size_of_val(&code[..]),
+        None => 0,
+    };
+    size_of::<Function>() + function.args.len() * size_of::<Symbol>() + tokens_size(&function.func) + code
+}
+
+impl CalcEngine {
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            variables: self.variables.len(),
+            functions: self.functions.len(),
+            programs: self.programs.len(),
+            names: self.symbols.len(),
+            bytes: self.saved_bytes + self.symbols.size(),
+        }
+    }
+
+    // adds up everything that is saved from the start, after that the total is kept up to date as things change
+    // done before each line and program, so changes made straight to the maps, like the ui's, get counted
+    pub fn recount_memory(&mut self) {
+        let mut bytes = 0;
+        bytes += self.variables.values().map(token_size).sum::<usize>();
+        bytes += self.formulas.values().map(|expr| tokens_size(expr)).sum::<usize>();
+        bytes += self.functions.values().map(function_size).sum::<usize>();
+        bytes += self.prev_answers.iter().map(token_size).sum::<usize>();
+        for (name, program) in self.programs.iter() {
+            bytes += name.len() + program.lines.iter().map(|line| line.len() + size_of::<Token>()).sum::<usize>();
+        }
+        self.saved_bytes = bytes;
+    }
+
+    // keeps the running total up to date when something saved is replaced
+    fn account(&mut self, added: usize, removed: usize) {
+        self.saved_bytes = (self.saved_bytes + added).saturating_sub(removed);
+    }
+
+    // these save something and count it, the old size is taken off if it replaced something
+    pub fn insert_variable(&mut self, name: Symbol, value: Token) {
+        let added = token_size(&value);
+        let removed = self.variables.insert(name, value).map_or(0, |old| token_size(&old));
+        self.account(added, removed);
+    }
+
+    pub fn remove_variable(&mut self, name: Symbol) {
+        let removed = self.variables.remove(&name).map_or(0, |old| token_size(&old));
+        self.account(0, removed);
+    }
+
+    pub fn insert_formula(&mut self, name: Symbol, expr: Vec<Token>) {
+        let added = tokens_size(&expr);
+        let removed = self.formulas.insert(name, expr).map_or(0, |old| tokens_size(&old));
+        self.account(added, removed);
+    }
+
+    pub fn remove_formula(&mut self, name: Symbol) {
+        let removed = self.formulas.remove(&name).map_or(0, |old| tokens_size(&old));
+        self.account(0, removed);
+    }
+
+    pub fn insert_function(&mut self, function: Function) {
+        let added = function_size(&function);
+        let removed = self.functions.insert(function.name, function).map_or(0, |old| function_size(&old));
+        self.account(added, removed);
+    }
+
+    pub fn push_answer(&mut self, answer: Token) {
+        let added = token_size(&answer);
+        self.prev_answers.insert(0, answer);
+        let removed = self.prev_answers.drain(ANSWER_COUNT.min(self.prev_answers.len())..).map(|old| token_size(&old)).sum();
+        self.account(added, removed);
+    }
+
+    // the line is checked first, since a program can't be saved if it doesn't fit
+    pub fn add_program_line(&mut self, name: &[u8], line: Vec<u8>) -> Result<(), &'static str> {
+        let size = line.len() + size_of::<Token>();
+        self.check_room(size, 0)?;
+        if let Some(program) = self.programs.get_mut(name) {
+            program.lines.push(line);
+            self.account(size, 0);
+        }
+        Ok(())
+    }
+
+    pub fn remove_program_line(&mut self, name: &[u8]) {
+        if let Some(line) = self.programs.get_mut(name).and_then(|program| program.lines.pop()) {
+            self.account(0, line.len() + size_of::<Token>());
+        }
+    }
+
+    // forgets the names nothing uses any more, like deleted variables or ones that were only typed by mistake
+    // nothing can be holding a symbol for them, so this is only done between lines and not during a program
+    pub fn free_names(&mut self) {
+        let mut used: BTreeSet<Symbol> = self.builtins.symbols().collect();
+        used.extend(self.variables.keys());
+        for (name, expr) in &self.formulas {
+            used.insert(*name);
+            used.extend(symbols_in(expr));
+        }
+        for function in self.functions.values() {
+            used.insert(function.name);
+            used.extend(&function.args);
+            used.extend(symbols_in(&function.func));
+        }
+        self.symbols.retain(|symbol| used.contains(&symbol));
+    }
+
+    // errors if there isn't room for something new this big, freed is how much it replaces
+    fn check_room(&self, size: usize, freed: usize) -> Result<(), &'static str> {
+        if (self.memory_usage().bytes + size).saturating_sub(freed) > MAX_MEMORY {
+            return Err("out of memory");
+        }
+        Ok(())
+    }
+
+    // errors if saving value in name would go over a limit
+    pub fn check_variable(&self, name: Symbol, value: &Token) -> Result<(), &'static str> {
+        if let List(values) = value {
+            if values.len() > MAX_LIST_LENGTH {
+                return Err("list is too long");
+            }
+        }
+        let old = self.variables.get(&name);
+        if old.is_none() && self.variables.len() >= MAX_VARIABLES {
+            return Err("too many variables");
+        }
+        self.check_room(token_size(value), old.map(token_size).unwrap_or(0))
+    }
+
+    pub fn check_formula(&self, name: Symbol, expr: &[Token]) -> Result<(), &'static str> {
+        if expr.len() > MAX_DEFINITION_LENGTH {
+            return Err("formula is too long");
+        }
+        if !self.variables.contains_key(&name) && self.variables.len() >= MAX_VARIABLES {
+            return Err("too many variables");
+        }
+        let old = self.formulas.get(&name).map(|old| tokens_size(old)).unwrap_or(0);
+        self.check_room(tokens_size(expr), old)
+    }
+
+    pub fn check_function(&self, function: &Function) -> Result<(), &'static str> {
+        if function.func.len() > MAX_DEFINITION_LENGTH {
+            return Err("function is too long");
+        }
+        let old = self.functions.get(&function.name);
+        if old.is_none() && self.functions.len() >= MAX_FUNCTIONS {
+            return Err("too many functions");
+        }
+        self.check_room(function_size(function), old.map(function_size).unwrap_or(0))
+    }
+
+}
@@ -0,0 +1,139 @@
+use alloc::vec::Vec;
+
+use libm::{fabs, floor, pow};
+
+use crate::token::Token::{self, *};
+
+use super::{
+    builtin::Ode,
+    core::{CalcEngine, ResultTable},
+    math_function::{FunctionHandling, MAX_TERMS},
+    solve::Solver,
+};
+
+// how close ode45 tries to keep every step when no tolerance is given
+const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+impl CalcEngine {
+    // ode(f, t0, y0, t1, steps) with RK4, or ode45(f, t0, y0, t1, tolerance) with adaptive RK45
+    // every (t, y) goes in the table, and y at t1 is the answer
+    pub fn solve_ode(&mut self, method: Ode, args: &[Vec<Token>]) -> Result<Token, &'static str> {
+        let function = match &args[0][..] {
+            [Variable(function)] => *function,
+            _ => return Err("f must be a function name"),
+        };
+        match self.functions.get(&function) {
+            Some(f) if f.arg_count == 2 => (),
+            _ => return Err("f must be a function f(t, y)"),
+        }
+
+        let mut numbers: Vec<f64> = Vec::new();
+        for arg in &args[1..] {
+            match self.solve(arg.clone())?[0] {
+                Number(n) if n.is_finite() => numbers.push(n),
+                _ => return Err("ode arguments must be numbers"),
+            }
+        }
+        let (t0, y0, t1) = (numbers[0], numbers[1], numbers[2]);
+
+        let mut f = |t: f64, y: f64| -> Result<f64, &'static str> {
+            match self.solve_function(function, &[Number(t), Comma, Number(y)])? {
+                Number(n) if n.is_finite() => Ok(n),
+                _ => Err("f must give a number"),
+            }
+        };
+        let mut table = ResultTable::new(&["t", "y"]);
+        let answer = if method == Ode::Rk4 {
+            let steps = numbers[3];
+            if steps < 1.0 || steps > MAX_TERMS || steps != floor(steps) {
+                return Err("steps must be a whole number from 1 to 10000");
+            }
+            rk4(&mut f, t0, y0, t1, steps as usize, &mut table)?
+        } else {
+            let tolerance = numbers.get(3).copied().unwrap_or(DEFAULT_TOLERANCE);
+            if tolerance <= 0.0 {
+                return Err("tolerance must be more than 0");
+            }
+            rk45(&mut f, t0, y0, t1, tolerance, &mut table)?
+        };
+
+        self.table = Some(table);
+        Ok(Number(answer))
+    }
+}
+
+// the classic 4th order Runge-Kutta with fixed steps, gives y at t1
+fn rk4<F>(f: &mut F, t0: f64, y0: f64, t1: f64, steps: usize, table: &mut ResultTable) -> Result<f64, &'static str>
+where
+    F: FnMut(f64, f64) -> Result<f64, &'static str>,
+{
+    let h = (t1 - t0) / steps as f64;
+    table.push(&[t0, y0]);
+    let mut y = y0;
+    for step in 0..steps {
+        let t = t0 + h * step as f64;
+        let k1 = f(t, y)?;
+        let k2 = f(t + h / 2.0, y + h * k1 / 2.0)?;
+        let k3 = f(t + h / 2.0, y + h * k2 / 2.0)?;
+        let k4 = f(t + h, y + h * k3)?;
+        y += h * (k1 + 2.0 * k2 + 2.0 * k3 + k4) / 6.0;
+        table.push(&[t0 + h * (step + 1) as f64, y]);
+    }
+    Ok(y)
+}
+
+// Runge-Kutta-Fehlberg, the step grows where y is smooth and shrinks where it isn't
+fn rk45<F>(f: &mut F, t0: f64, y0: f64, t1: f64, tolerance: f64, table: &mut ResultTable) -> Result<f64, &'static str>
+where
+    F: FnMut(f64, f64) -> Result<f64, &'static str>,
+{
+    table.push(&[t0, y0]);
+    let (mut t, mut y) = (t0, y0);
+    let mut h = (t1 - t0) / 100.0;
+    let mut tries = 0.0;
+
+    while (t1 - t) * h > 0.0 {
+        tries += 1.0;
+        if tries > MAX_TERMS {
+            return Err("ode45 took too many steps");
+        }
+        // don't step past the end
+        if (t + h - t1) * h > 0.0 {
+            h = t1 - t;
+        }
+
+        let k1 = h * f(t, y)?;
+        let k2 = h * f(t + h / 4.0, y + k1 / 4.0)?;
+        let k3 = h * f(t + 3.0 * h / 8.0, y + 3.0 * k1 / 32.0 + 9.0 * k2 / 32.0)?;
+        let k4 = h * f(
+            t + 12.0 * h / 13.0,
+            y + 1932.0 * k1 / 2197.0 - 7200.0 * k2 / 2197.0 + 7296.0 * k3 / 2197.0,
+        )?;
+        let k5 = h * f(
+            t + h,
+            y + 439.0 * k1 / 216.0 - 8.0 * k2 + 3680.0 * k3 / 513.0 - 845.0 * k4 / 4104.0,
+        )?;
+        let k6 = h * f(
+            t + h / 2.0,
+            y - 8.0 * k1 / 27.0 + 2.0 * k2 - 3544.0 * k3 / 2565.0 + 1859.0 * k4 / 4104.0 - 11.0 * k5 / 40.0,
+        )?;
+        let fourth = y + 25.0 * k1 / 216.0 + 1408.0 * k3 / 2565.0 + 2197.0 * k4 / 4104.0 - k5 / 5.0;
+        let fifth = y + 16.0 * k1 / 135.0 + 6656.0 * k3 / 12825.0 + 28561.0 * k4 / 56430.0
+            - 9.0 * k5 / 50.0
+            + 2.0 * k6 / 55.0;
+
+        // the difference between the 4th and 5th order answers is how far off the step is
+        let error = fabs(fifth - fourth);
+        let allowed = tolerance * (1.0 + fabs(y));
+        if error <= allowed {
+            t += h;
+            y = fifth;
+            table.push(&[t, y]);
+        } else if fabs(h) < 1e-12 * (1.0 + fabs(t)) {
+            return Err("ode45 step got too small");
+        }
+        let scale = if error == 0.0 { 4.0 } else { 0.9 * pow(allowed / error, 0.2) };
+        h *= scale.clamp(0.1, 4.0);
+    }
+    Ok(y)
+}
@@ -0,0 +1,172 @@
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use libm::{acos, cbrt, copysign, cos, fabs, hypot, sin, sqrt};
+
+// a complex number as (real, imaginary), only used for roots
+pub type Complex = (f64, f64);
+
+pub struct Roots {
+    pub roots: Vec<Complex>,
+    // false if Durand-Kerner ran out of rounds before the roots settled, so they are only close
+    pub settled: bool,
+}
+
+// how many rounds Durand-Kerner gets to settle, and when it counts as settled
+const MAX_ITERATIONS: usize = 500;
+const TOLERANCE: f64 = 1e-12;
+
+// every root of the polynomial, coefficients go from the highest power down, eg. x^2 - 3x + 2 is [1, -3, 2]
+pub fn poly_roots(coefficients: &[f64]) -> Result<Roots, &'static str> {
+    if coefficients.iter().any(|c| !c.is_finite()) {
+        return Err("coefficients must be finite");
+    }
+
+    // leading zeros don't change the polynomial
+    let start = coefficients.iter().position(|c| *c != 0.0).unwrap_or(coefficients.len());
+    let mut coefficients = coefficients[start..].to_vec();
+    if coefficients.len() < 2 {
+        return Err("polynomial needs a degree of at least 1");
+    }
+
+    // trailing zeros are roots at 0, eg. x^3 - x^2 is x^2 (x - 1)
+    let mut roots: Vec<Complex> = Vec::new();
+    while coefficients.len() > 1 && coefficients[coefficients.len() - 1] == 0.0 {
+        coefficients.pop();
+        roots.push((0.0, 0.0));
+    }
+
+    // make it monic, so the highest power has a coefficient of 1
+    let lead = coefficients[0];
+    let monic: Vec<f64> = coefficients.iter().map(|c| c / lead).collect();
+
+    let mut settled = true;
+    match monic.len() - 1 {
+        0 => (),
+        1 => roots.push((-monic[1], 0.0)),
+        2 => roots.extend(quadratic_roots(monic[1], monic[2])),
+        3 => roots.extend(cubic_roots(monic[1], monic[2], monic[3])),
+        _ => settled = durand_kerner(&monic, &mut roots)?,
+    }
+
+    // numbers that are only a rounding error away from real are real, and -0 is just 0
+    for root in &mut roots {
+        if fabs(root.1) < 1e-9 * fabs(root.0).max(1.0) {
+            root.1 = 0.0;
+        }
+        root.0 += 0.0;
+        root.1 += 0.0;
+    }
+    roots.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap_or(core::cmp::Ordering::Equal));
+    Ok(Roots { roots, settled })
+}
+
+// x^2 + bx + c
+fn quadratic_roots(b: f64, c: f64) -> [Complex; 2] {
+    let discriminant = b * b - 4.0 * c;
+    if discriminant >= 0.0 {
+        // the sign of b is used so the two big numbers are never subtracted, which would lose precision
+        let q = -0.5 * (b + copysign(sqrt(discriminant), b));
+        if q == 0.0 {
+            return [(0.0, 0.0), (0.0, 0.0)];
+        }
+        [(q, 0.0), (c / q, 0.0)]
+    } else {
+        let imaginary = sqrt(-discriminant) / 2.0;
+        [(-b / 2.0, imaginary), (-b / 2.0, -imaginary)]
+    }
+}
+
+// x^3 + bx^2 + cx + d, by turning it into t^3 + pt + q with x = t - b/3
+fn cubic_roots(b: f64, c: f64, d: f64) -> [Complex; 3] {
+    let shift = -b / 3.0;
+    let p = c - b * b / 3.0;
+    let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+    let discriminant = (q / 2.0) * (q / 2.0) + (p / 3.0) * (p / 3.0) * (p / 3.0);
+
+    if discriminant > 0.0 || p == 0.0 {
+        // one real root and two complex ones, Cardano's formula
+        let root = sqrt(discriminant.max(0.0));
+        let u = cbrt(-q / 2.0 + root);
+        let v = cbrt(-q / 2.0 - root);
+        let real = -(u + v) / 2.0 + shift;
+        let imaginary = (u - v) * sqrt(3.0) / 2.0;
+        [(u + v + shift, 0.0), (real, imaginary), (real, -imaginary)]
+    } else {
+        // three real roots, the trigonometric form avoids complex cube roots
+        let r = 2.0 * sqrt(-p / 3.0);
+        let angle = acos((3.0 * q / (p * r)).clamp(-1.0, 1.0)) / 3.0;
+        [
+            (r * cos(angle) + shift, 0.0),
+            (r * cos(angle - 2.0 * PI / 3.0) + shift, 0.0),
+            (r * cos(angle - 4.0 * PI / 3.0) + shift, 0.0),
+        ]
+    }
+}
+
+// finds all the roots of a monic polynomial at once, each guess is pushed away from the others every round
+// adds them to found, and says if they settled
+fn durand_kerner(monic: &[f64], found: &mut Vec<Complex>) -> Result<bool, &'static str> {
+    let degree = monic.len() - 1;
+
+    // every root is inside the Cauchy bound, so the guesses are spread around a circle that size
+    // the angle is turned a little so they aren't symmetric like the roots of a real polynomial often are
+    let bound = 1.0 + monic[1..].iter().fold(0.0, |biggest: f64, c| biggest.max(fabs(*c)));
+    let mut roots: Vec<Complex> = Vec::with_capacity(degree);
+    for k in 0..degree {
+        let angle = 2.0 * PI * k as f64 / degree as f64 + 0.4;
+        roots.push((bound * cos(angle), bound * sin(angle)));
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut biggest_change: f64 = 0.0;
+        for i in 0..degree {
+            let mut denominator: Complex = (1.0, 0.0);
+            for j in 0..degree {
+                if i != j {
+                    denominator = multiply(denominator, subtract(roots[i], roots[j]));
+                }
+            }
+            let change = divide(evaluate(monic, roots[i]), denominator);
+            if !change.0.is_finite() || !change.1.is_finite() {
+                return Err("roots did not converge");
+            }
+            roots[i] = subtract(roots[i], change);
+            biggest_change = biggest_change.max(hypot(change.0, change.1));
+        }
+        if biggest_change < TOLERANCE * bound {
+            found.extend(roots);
+            return Ok(true);
+        }
+    }
+
+    // repeated roots settle slowly, so they are close but not exact
+    found.extend(roots);
+    Ok(false)
+}
+
+// the value of the polynomial at x, with Horner's method
+fn evaluate(coefficients: &[f64], x: Complex) -> Complex {
+    let mut value: Complex = (0.0, 0.0);
+    for c in coefficients {
+        value = multiply(value, x);
+        value.0 += c;
+    }
+    value
+}
+
+fn subtract(a: Complex, b: Complex) -> Complex {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn multiply(a: Complex, b: Complex) -> Complex {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn divide(a: Complex, b: Complex) -> Complex {
+    let denominator = b.0 * b.0 + b.1 * b.1;
+    (
+        (a.0 * b.0 + a.1 * b.1) / denominator,
+        (a.1 * b.0 - a.0 * b.1) / denominator,
+    )
+}
@@ -0,0 +1,125 @@
+use alloc::{format, vec::Vec};
+
+use libm::fabs;
+
+use crate::token::Token::{self, *};
+
+use super::{core::CalcEngine, symbol::{Interner, Symbol}};
+
+// turns tokens back into text that gives the same answer when typed in, args are the names of the FunctionArgs
+// lists can't be typed in, so there is no text for anything with one in it
+pub fn to_text(tokens: &[Token], symbols: &Interner, args: &[Symbol]) -> Option<Vec<u8>> {
+    let mut text = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Number(n) => {
+                // a negative number is one token, but -2^2 would be read as -(2^2)
+                let bracketed = *n < 0.0 && n.is_finite() && matches!(tokens.get(i + 1), Some(Exponentation | Factorial | Percent));
+                if bracketed {
+                    text.push(b'(');
+                }
+                text.extend(number_text(*n));
+                if bracketed {
+                    text.push(b')');
+                }
+            }
+            Uncertain(value, uncertainty) => {
+                text.push(b'(');
+                text.extend(number_text(*value));
+                text.extend_from_slice(b"+/-");
+                text.extend(number_text(*uncertainty));
+                text.push(b')');
+            }
+            List(_) => return None,
+            UnresolvedString(name) | Variable(name) | FunctionName(name) => text.extend_from_slice(symbols.name(*name)),
+            FunctionArg(index) => match args.get(*index) {
+                Some(name) => text.extend_from_slice(symbols.name(*name)),
+                None => text.extend(format!("arg{}", index + 1).into_bytes()),
+            },
+            Let => text.extend_from_slice(b"let "),
+            _ => text.extend_from_slice(operator_text(token)),
+        }
+    }
+    Some(text)
+}
+
+fn operator_text(token: &Token) -> &'static [u8] {
+    match token {
+        Addition => b"+",
+        Subtraction | Negation => b"-",
+        Division => b"/",
+        Multiplication => b"*",
+        Truncation => b"//",
+        Modulation | Percent => b"%",
+        Exponentation => b"^",
+        Factorial => b"!",
+        PlusMinus => b"+/-",
+        Less => b"<",
+        Greater => b">",
+        LessEqual => b"<=",
+        GreaterEqual => b">=",
+        Equal => b"==",
+        NotEqual => b"!=",
+        BitwiseLeftShift => b"<<",
+        BitwiseRightShift => b">>",
+        BitwiseAnd => b"&",
+        BitwiseOr => b"|",
+        BitwiseXor => b"^^",
+        LeftBracket => b"(",
+        RightBracket => b")",
+        Comma => b",",
+        Semicolon => b";",
+        Assignment => b"=",
+        Definition => b":=",
+        _ => b"",
+    }
+}
+
+// every digit that is needed to get the same number back, with E for very big and small ones like the E key
+// infinity and NaN are written as the division that gives them
+fn number_text(n: f64) -> Vec<u8> {
+    let magnitude = fabs(n);
+    if n.is_nan() {
+        b"(0/0)".to_vec()
+    } else if n == f64::INFINITY {
+        b"(1/0)".to_vec()
+    } else if n == f64::NEG_INFINITY {
+        b"(-1/0)".to_vec()
+    } else if n == 0.0 || (1e-4..1e10).contains(&magnitude) {
+        format!("{}", n).into_bytes()
+    } else {
+        format!("{:E}", n).into_bytes()
+    }
+}
+
+impl CalcEngine {
+    // a user function the way it would be typed, eg. hypot(a,b)=sqrt(a*a+b*b), None if it has a list in it
+    pub fn function_text(&self, name: Symbol) -> Option<Vec<u8>> {
+        let function = self.functions.get(&name)?;
+        let mut text = self.symbols.name(name).to_vec();
+        text.push(b'(');
+        for (i, arg) in function.args.iter().enumerate() {
+            if i > 0 {
+                text.push(b',');
+            }
+            text.extend_from_slice(self.symbols.name(*arg));
+        }
+        text.push(b')');
+        text.extend_from_slice(if function.late_binding { b":=" } else { b"=" });
+        text.extend(to_text(&function.func, &self.symbols, &function.args)?);
+        Some(text)
+    }
+
+    // a variable the way it would be typed, formulas keep their expression, eg. area:=w*h, None for lists
+    pub fn variable_text(&self, name: Symbol) -> Option<Vec<u8>> {
+        let mut text = self.symbols.name(name).to_vec();
+        if let Some(expr) = self.formulas.get(&name) {
+            text.extend_from_slice(b":=");
+            text.extend(to_text(expr, &self.symbols, &[])?);
+        } else {
+            text.push(b'=');
+            text.extend(to_text(core::slice::from_ref(self.variables.get(&name)?), &self.symbols, &[])?);
+        }
+        Some(text)
+    }
+}
@@ -0,0 +1,333 @@
+use alloc::vec::Vec;
+
+use crate::token::{tokenize, Token::{self, *}};
+
+use super::{
+    core::{CalcEngine, OutputLine},
+    solve::{split_arguments, Solver},
+    symbol::{Interner, Symbol},
+    token::TokenHandling,
+    validate::validate_token_list,
+};
+
+#[derive(Clone)]
+pub struct Program {
+    pub lines: Vec<Vec<u8>>,
+}
+
+// a line of a program, blocks know where the line that closes them is
+enum Instruction {
+    For { var: Symbol, start: Vec<Token>, end: Vec<Token>, step: Vec<Token>, end_index: usize },
+    While { condition: Vec<Token>, end_index: usize },
+    If { condition: Vec<Token>, else_index: Option<usize>, end_index: usize },
+    Else { end_index: usize },
+    End { start_index: usize },
+    Disp(Vec<Token>),
+    Prompt(Symbol),
+    // anything else is run like a line typed in by the user
+    Line(Vec<u8>),
+}
+
+// a program that has started running
+pub struct ProgramRun {
+    instructions: Vec<Instruction>,
+    pc: usize,
+    // the last value and step of each running for loop, by the index of its for line
+    loops: Vec<(usize, f64, f64)>,
+    // the variable a prompt is waiting on
+    waiting_for: Option<Symbol>,
+}
+
+pub enum RunStatus {
+    Finished,
+    // waiting for the user to give this variable a value
+    Prompt(Vec<u8>),
+}
+
+pub trait ProgramHandling {
+    fn run_program(&mut self, name: &[u8]) -> Result<RunStatus, &'static str>;
+    fn answer_prompt(&mut self, input: &[u8]) -> Result<RunStatus, &'static str>;
+    fn continue_program(&mut self) -> Result<RunStatus, &'static str>;
+}
+
+impl ProgramHandling for CalcEngine {
+    fn run_program(&mut self, name: &[u8]) -> Result<RunStatus, &'static str> {
+        self.budget.start();
+        self.recount_memory();
+        let instructions = match self.programs.get(name) {
+            Some(program) => compile_program(&program.lines, &mut self.symbols)?,
+            None => return Err("program does not exist"),
+        };
+        self.running = Some(ProgramRun {
+            instructions,
+            pc: 0,
+            loops: Vec::new(),
+            waiting_for: None,
+        });
+        self.continue_program()
+    }
+
+    // gives the variable the running program is waiting on a value, then keeps going
+    fn answer_prompt(&mut self, input: &[u8]) -> Result<RunStatus, &'static str> {
+        let name = match self.running.as_mut().and_then(|run| run.waiting_for.take()) {
+            Some(name) => name,
+            None => return Err("no program is waiting"),
+        };
+        self.budget.start();
+        let value = match self.tokenize(input) {
+            Ok(tokens) => self.evaluate(tokens),
+            Err(e) => Err(e),
+        };
+        match value {
+            Ok(value) => match self.set_variable(name, value) {
+                Ok(()) => self.continue_program(),
+                Err(e) => {
+                    self.running = None;
+                    Err(e)
+                }
+            },
+            Err(e) => {
+                self.running = None;
+                Err(e)
+            }
+        }
+    }
+
+    fn continue_program(&mut self) -> Result<RunStatus, &'static str> {
+        let result = self.run_until_prompt();
+        if !matches!(result, Ok(RunStatus::Prompt(_))) {
+            self.running = None;
+        }
+        result
+    }
+}
+
+impl CalcEngine {
+    fn run_until_prompt(&mut self) -> Result<RunStatus, &'static str> {
+        // the run is taken out while it goes, so lines can still use the rest of the engine
+        let mut run = match self.running.take() {
+            Some(run) => run,
+            None => return Err("no program is running"),
+        };
+
+        let result = loop {
+            if run.pc >= run.instructions.len() {
+                break Ok(RunStatus::Finished);
+            }
+            // every line counts against the budget, so an endless loop runs out of steps or gets cancelled with B
+            if let Err(e) = self.step() {
+                break Err(e);
+            }
+            match self.run_instruction(&mut run) {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => (),
+                Err(e) => break Err(e),
+            }
+        };
+
+        self.running = Some(run);
+        result
+    }
+
+    // runs the line at pc and moves pc to the next line to run
+    fn run_instruction(&mut self, run: &mut ProgramRun) -> Result<Option<RunStatus>, &'static str> {
+        let pc = run.pc;
+        run.pc += 1;
+        match &run.instructions[pc] {
+            Instruction::For { var, start, end, step, end_index } => {
+                let start = self.eval_number(start.clone())?;
+                let end = self.eval_number(end.clone())?;
+                let step = if step.is_empty() { 1.0 } else { self.eval_number(step.clone())? };
+                if step == 0.0 {
+                    return Err("for step can't be 0");
+                }
+                self.set_variable(*var, Number(start))?;
+                run.loops.retain(|(index, _, _)| *index != pc);
+                run.loops.push((pc, end, step));
+                if !in_range(start, end, step) {
+                    run.pc = end_index + 1;
+                }
+            }
+            Instruction::While { condition, end_index } => {
+                if self.eval_number(condition.clone())? == 0.0 {
+                    run.pc = end_index + 1;
+                }
+            }
+            Instruction::If { condition, else_index, end_index } => {
+                if self.eval_number(condition.clone())? == 0.0 {
+                    run.pc = match else_index {
+                        Some(else_index) => else_index + 1,
+                        None => end_index + 1,
+                    };
+                }
+            }
+            Instruction::Else { end_index } => {
+                // only reached at the end of the if part, so skip the else part
+                run.pc = end_index + 1;
+            }
+            Instruction::End { start_index } => match &run.instructions[*start_index] {
+                Instruction::For { var, .. } => {
+                    let (end, step) = match run.loops.iter().find(|(index, _, _)| index == start_index) {
+                        Some((_, end, step)) => (*end, *step),
+                        None => return Err("end without a for"),
+                    };
+                    let value = match self.variables.get(var) {
+                        Some(Number(n)) => n + step,
+                        _ => return Err("for variable was removed"),
+                    };
+                    self.set_variable(*var, Number(value))?;
+                    if in_range(value, end, step) {
+                        run.pc = start_index + 1;
+                    }
+                }
+                Instruction::While { .. } => {
+                    run.pc = *start_index;
+                }
+                _ => (),
+            },
+            Instruction::Disp(expr) => {
+                let value = self.evaluate(expr.clone())?;
+                self.output.push(OutputLine::Value(Vec::new(), value));
+            }
+            Instruction::Prompt(var) => {
+                run.waiting_for = Some(*var);
+                return Ok(Some(RunStatus::Prompt(self.symbols.name(*var).to_vec())));
+            }
+            Instruction::Line(line) => {
+                self.run_line(line)?;
+            }
+        }
+        Ok(None)
+    }
+
+    // checks, resolves and solves an expression without touching ans
+    pub fn eval_expression(&mut self, tokens: Vec<Token>) -> Result<Token, &'static str> {
+        self.budget.start();
+        self.evaluate(tokens)
+    }
+
+    // eval_expression for the lines of a program, which all share the program's budget
+    fn evaluate(&mut self, mut tokens: Vec<Token>) -> Result<Token, &'static str> {
+        if let Some(e) = validate_token_list(&tokens) {
+            return Err(e);
+        }
+        if let Some(e) = self.resolve_variables(&mut tokens) {
+            return Err(e);
+        }
+        match self.solve(tokens) {
+            Ok(answer) => Ok(answer[0].clone()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn eval_number(&mut self, tokens: Vec<Token>) -> Result<f64, &'static str> {
+        match self.evaluate(tokens)? {
+            Number(n) => Ok(n),
+            _ => Err("expected a number"),
+        }
+    }
+}
+
+// turns the lines of a program into instructions, and matches up every block with its end
+fn compile_program(lines: &[Vec<u8>], symbols: &mut Interner) -> Result<Vec<Instruction>, &'static str> {
+    let mut instructions: Vec<Instruction> = Vec::with_capacity(lines.len());
+    // indexes of the for, while, if and else lines that haven't been closed yet
+    let mut open_blocks: Vec<usize> = Vec::new();
+
+    for line in lines {
+        let index = instructions.len();
+        let (keyword, rest) = split_keyword(line);
+        let instruction = match keyword {
+            b"for" => {
+                let mut tokens = tokenize(rest, symbols)?;
+                // the arguments can be in brackets, eg. for(i,1,10)
+                if tokens.first() == Some(&LeftBracket) && tokens.last() == Some(&RightBracket) {
+                    tokens.pop();
+                    tokens.remove(0);
+                }
+                let parts = split_arguments(&tokens);
+                if parts.len() < 3 || parts.len() > 4 {
+                    return Err("for needs a variable, start, end and maybe a step");
+                }
+                let var = match &parts[0][..] {
+                    [Variable(name)] => *name,
+                    _ => return Err("for needs a variable"),
+                };
+                open_blocks.push(index);
+                Instruction::For {
+                    var,
+                    start: parts[1].clone(),
+                    end: parts[2].clone(),
+                    step: parts.get(3).cloned().unwrap_or_default(),
+                    end_index: 0,
+                }
+            }
+            b"while" => {
+                open_blocks.push(index);
+                Instruction::While { condition: tokenize(rest, symbols)?, end_index: 0 }
+            }
+            b"if" => {
+                open_blocks.push(index);
+                Instruction::If { condition: tokenize(rest, symbols)?, else_index: None, end_index: 0 }
+            }
+            b"else" => {
+                match open_blocks.last().map(|i| &mut instructions[*i]) {
+                    Some(Instruction::If { else_index: else_index @ None, .. }) => *else_index = Some(index),
+                    _ => return Err("else without an if"),
+                }
+                open_blocks.push(index);
+                Instruction::Else { end_index: 0 }
+            }
+            b"end" => {
+                let mut start_index = match open_blocks.pop() {
+                    Some(start_index) => start_index,
+                    None => return Err("end without a block"),
+                };
+                // an else closes its if too
+                if let Instruction::Else { end_index } = &mut instructions[start_index] {
+                    *end_index = index;
+                    start_index = open_blocks.pop().unwrap();
+                }
+                match &mut instructions[start_index] {
+                    Instruction::For { end_index, .. }
+                    | Instruction::While { end_index, .. }
+                    | Instruction::If { end_index, .. } => *end_index = index,
+                    _ => (),
+                }
+                Instruction::End { start_index }
+            }
+            b"disp" => Instruction::Disp(tokenize(rest, symbols)?),
+            b"prompt" => match &tokenize(rest, symbols)?[..] {
+                [Variable(name)] => Instruction::Prompt(*name),
+                _ => return Err("prompt needs a variable"),
+            },
+            _ => Instruction::Line(line.clone()),
+        };
+        instructions.push(instruction);
+    }
+
+    if !open_blocks.is_empty() {
+        return Err("block is missing an end");
+    }
+    Ok(instructions)
+}
+
+// whether a for loop at value still has more to go
+fn in_range(value: f64, end: f64, step: f64) -> bool {
+    if step < 0.0 {
+        value >= end
+    } else {
+        value <= end
+    }
+}
+
+// splits the first word off a line if it is a keyword, eg. "disp x" is ("disp", " x")
+fn split_keyword(line: &[u8]) -> (&[u8], &[u8]) {
+    let start = line.iter().position(|c| *c != b' ').unwrap_or(line.len());
+    // the whole name is read, so end_x=3 or if1=2 are lines and not keywords
+    let end = line[start..].iter().position(|c| !c.is_ascii_alphanumeric() && *c != b'_').map_or(line.len(), |i| i + start);
+    match &line[start..end] {
+        b"for" | b"while" | b"if" | b"else" | b"end" | b"disp" | b"prompt" => (&line[start..end], &line[end..]),
+        _ => (b"", line),
+    }
+}
@@ -0,0 +1,135 @@
+use alloc::vec::Vec;
+use core::f64::consts::TAU;
+
+use libm::{cos, floor, log, sqrt};
+
+use crate::token::Token::{self, *};
+
+use super::{builtin::Random, core::CalcEngine, solve::Solver};
+
+// xorshift64*, small and fast, and plenty random for demos and monte carlo checks
+pub struct Rng {
+    state: u64,
+    // once seed() is used the numbers have to repeat, so entropy stops being mixed in
+    pub seeded: bool,
+}
+
+impl Default for Rng {
+    fn default() -> Rng {
+        Rng::new()
+    }
+}
+
+impl Rng {
+    pub fn new() -> Rng {
+        Rng {
+            state: splitmix(0),
+            seeded: false,
+        }
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        self.state = splitmix(seed);
+        self.seeded = true;
+    }
+
+    // stirs something unpredictable into the state, like a timer value when a button is pressed
+    pub fn mix(&mut self, entropy: u64) {
+        if !self.seeded {
+            self.state = splitmix(self.state ^ entropy);
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    // from 0 up to but not including 1
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// scrambles a number so similar seeds give very different states, and the state is never 0
+fn splitmix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+    if z == 0 {
+        0x9E3779B97F4A7C15
+    } else {
+        z
+    }
+}
+
+impl CalcEngine {
+    // rand(), randint(a, b), randnorm(mu, sigma), randsample(list) and seed(n)
+    pub fn solve_random(&mut self, function: Random, arg_tokens: &[Vec<Token>]) -> Result<Token, &'static str> {
+        // rand() has one empty argument
+        let mut args: Vec<Token> = Vec::new();
+        if arg_tokens.len() > 1 || arg_tokens.iter().any(|arg| !arg.is_empty()) {
+            for arg in arg_tokens {
+                args.push(self.solve(arg.clone())?[0].clone());
+            }
+        }
+        let answer = match function {
+            Random::Rand => self.rng.next_f64(),
+            Random::Randint => {
+                let (low, high) = match numbers(&args)?[..] {
+                    [low, high] => (low, high),
+                    _ => return Err("incorrect argument count"),
+                };
+                if low != floor(low) || high != floor(high) || low > high {
+                    return Err("randint needs whole numbers with a <= b");
+                }
+                floor(low + self.rng.next_f64() * (high - low + 1.0)).min(high)
+            }
+            Random::Randnorm => {
+                let (mu, sigma) = match numbers(&args)?[..] {
+                    [mu, sigma] => (mu, sigma),
+                    _ => return Err("incorrect argument count"),
+                };
+                if sigma < 0.0 {
+                    return Err("sigma can't be negative");
+                }
+                // Box-Muller, 1 - u so log never gets 0
+                let u = 1.0 - self.rng.next_f64();
+                let v = self.rng.next_f64();
+                mu + sigma * sqrt(-2.0 * log(u)) * cos(TAU * v)
+            }
+            // randsample picks from a list, or from all of its arguments
+            Random::Randsample => {
+                let choices: Vec<f64> = match &args[..] {
+                    [List(list)] => list.clone(),
+                    _ => numbers(&args)?,
+                };
+                if choices.is_empty() {
+                    return Err("randsample needs something to pick");
+                }
+                let index = (self.rng.next_f64() * choices.len() as f64) as usize;
+                choices[index.min(choices.len() - 1)]
+            }
+            Random::Seed => {
+                let seed = numbers(&args)?[0];
+                self.rng.seed(seed.to_bits());
+                seed
+            }
+        };
+        Ok(Number(answer))
+    }
+}
+
+fn numbers(args: &[Token]) -> Result<Vec<f64>, &'static str> {
+    let mut numbers: Vec<f64> = Vec::new();
+    for arg in args {
+        match arg {
+            Number(n) => numbers.push(*n),
+            _ => return Err("function arguments must be numbers"),
+        }
+    }
+    Ok(numbers)
+}
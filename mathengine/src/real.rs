@@ -0,0 +1,457 @@
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use libm::{acos, asin, atan, cos, log, log10, pow, round, sin, sqrt, tan};
+
+use crate::token::Token::{self, *};
+
+use super::core::CalcEngine;
+
+// which number type the math is done in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberMode {
+    // f64, which the gba has to do in software since it has no fpu
+    Precise,
+    // fixed point, which is just integer math so it is a lot faster, but only has about 6 decimal places
+    // numbers stay fixed point all the way through anything the vm can run, even sin and log
+    Fast,
+}
+
+impl NumberMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberMode::Precise => "PRECISE",
+            NumberMode::Fast => "FAST",
+        }
+    }
+}
+
+// what the solver needs from a number type
+pub trait Real:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self> + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn from_f64(n: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn from_constant(constant: Constant) -> Self;
+    fn pow(self, exponent: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan(self) -> Self;
+    fn ln(self) -> Self;
+    fn log10(self) -> Self;
+}
+
+// a number in both types, so a constant in compiled code isn't converted every time it is used
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constant {
+    pub precise: f64,
+    pub fast: Fixed,
+}
+
+impl Constant {
+    pub fn new(n: f64) -> Constant {
+        Constant {
+            precise: n,
+            fast: Fixed::from_f64(n),
+        }
+    }
+}
+
+impl Real for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    fn from_f64(n: f64) -> Self {
+        n
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn from_constant(constant: Constant) -> Self {
+        constant.precise
+    }
+    fn pow(self, exponent: Self) -> Self {
+        pow(self, exponent)
+    }
+    fn sqrt(self) -> Self {
+        sqrt(self)
+    }
+    fn sin(self) -> Self {
+        sin(self)
+    }
+    fn cos(self) -> Self {
+        cos(self)
+    }
+    fn tan(self) -> Self {
+        tan(self)
+    }
+    fn asin(self) -> Self {
+        asin(self)
+    }
+    fn acos(self) -> Self {
+        acos(self)
+    }
+    fn atan(self) -> Self {
+        atan(self)
+    }
+    fn ln(self) -> Self {
+        log(self)
+    }
+    fn log10(self) -> Self {
+        log10(self)
+    }
+}
+
+// how many of Fixed's bits are after the point, 2^-20 is about 0.000001
+const FRACTION_BITS: u32 = 20;
+const ONE: i64 = 1 << FRACTION_BITS;
+
+// a fixed point number, the value times 2^20 stored as an integer
+// anything that overflows or has no answer, like dividing by 0, becomes NAN and stays that way like f64's NaN
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Fixed(i64);
+
+const PI: Fixed = Fixed(3294199);
+const HALF_PI: Fixed = Fixed(1647099);
+const TAU: Fixed = Fixed(6588397);
+const QUARTER_PI: Fixed = Fixed(823550);
+const LN2: Fixed = Fixed(726817);
+const LN10: Fixed = Fixed(2414435);
+// 2pi with 32 more bits after the point, so big angles still come out right once the whole turns are taken out
+const TAU_WIDE: i128 = 28296951008113761;
+// tan(pi/8), atan's series is only used below this
+const TAN_EIGHTH_PI: Fixed = Fixed(434334);
+
+impl Fixed {
+    pub const NAN: Fixed = Fixed(i64::MIN);
+
+    fn from_wide(raw: i128) -> Fixed {
+        match i64::try_from(raw) {
+            Ok(raw) if raw != i64::MIN => Fixed(raw),
+            _ => Fixed::NAN,
+        }
+    }
+
+    fn is_nan(self) -> bool {
+        self == Fixed::NAN
+    }
+
+    fn abs(self) -> Fixed {
+        if self.0 < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+
+    // 1/n, for the terms of the series below
+    fn reciprocal(n: i64) -> Fixed {
+        Fixed(ONE / n)
+    }
+
+    // dividing by a whole number doesn't need the shift a full division does
+    fn div_int(self, n: i64) -> Fixed {
+        if self.is_nan() {
+            return Fixed::NAN;
+        }
+        Fixed(self.0 / n)
+    }
+
+    // log2(x) split into a whole part and ln of what's left, which is from 1 to 2
+    fn split_log(self) -> Option<(i64, Fixed)> {
+        if self.is_nan() || self.0 <= 0 {
+            return None;
+        }
+        let whole = 63 - self.0.leading_zeros() as i64 - FRACTION_BITS as i64;
+        let m = if whole >= 0 { Fixed(self.0 >> whole) } else { Fixed(self.0 << -whole) };
+        // ln(m) = 2(s + s^3/3 + s^5/5 + ...) where s = (m-1)/(m+1) is under 1/3
+        let s = (m - Fixed(ONE)) / (m + Fixed(ONE));
+        let s2 = s * s;
+        let mut sum = Fixed::reciprocal(15);
+        for k in (0..7).rev() {
+            sum = Fixed::reciprocal(2 * k + 1) + s2 * sum;
+        }
+        Some((whole, Fixed(2 * ONE) * s * sum))
+    }
+
+    fn log2(self) -> Fixed {
+        match self.split_log() {
+            Some((whole, ln_m)) => Fixed(whole << FRACTION_BITS) + ln_m / LN2,
+            None => Fixed::NAN,
+        }
+    }
+
+    // 2^self, done as a shift for the whole part and e^(f*ln2) for the rest
+    fn exp2(self) -> Fixed {
+        if self.is_nan() {
+            return Fixed::NAN;
+        }
+        let whole = self.0 >> FRACTION_BITS;
+        let t = Fixed(self.0 - (whole << FRACTION_BITS)) * LN2;
+        let mut sum = Fixed(ONE);
+        for k in (1..=10).rev() {
+            sum = Fixed(ONE) + (t * sum).div_int(k);
+        }
+        if whole < 0 {
+            return Fixed(sum.0 >> (-whole).min(63));
+        }
+        // anything that shifts bits off the top is too big
+        if whole >= 62 || (sum.0 << whole) >> whole != sum.0 {
+            return Fixed::NAN;
+        }
+        Fixed(sum.0 << whole)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, other: Fixed) -> Fixed {
+        if self.is_nan() || other.is_nan() {
+            return Fixed::NAN;
+        }
+        match self.0.checked_add(other.0) {
+            Some(raw) if raw != i64::MIN => Fixed(raw),
+            _ => Fixed::NAN,
+        }
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, other: Fixed) -> Fixed {
+        self + -other
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, other: Fixed) -> Fixed {
+        if self.is_nan() || other.is_nan() {
+            return Fixed::NAN;
+        }
+        // numbers under 2048 fit in 32 bits, so the product fits in 64 and skips the slow 128 bit math
+        if self.0 as i32 as i64 == self.0 && other.0 as i32 as i64 == other.0 {
+            return Fixed((self.0 as i32 as i64 * other.0 as i32 as i64) >> FRACTION_BITS);
+        }
+        Fixed::from_wide((self.0 as i128 * other.0 as i128) >> FRACTION_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, other: Fixed) -> Fixed {
+        if self.is_nan() || other.is_nan() || other.0 == 0 {
+            return Fixed::NAN;
+        }
+        // the same for division, as long as the shifted numerator still fits in 64 bits
+        if self.0.unsigned_abs() < 1 << (63 - FRACTION_BITS) {
+            return Fixed::from_wide(((self.0 << FRACTION_BITS) / other.0) as i128);
+        }
+        Fixed::from_wide(((self.0 as i128) << FRACTION_BITS) / other.0 as i128)
+    }
+}
+
+impl Rem for Fixed {
+    type Output = Fixed;
+    fn rem(self, other: Fixed) -> Fixed {
+        if self.is_nan() || other.is_nan() || other.0 == 0 {
+            return Fixed::NAN;
+        }
+        Fixed(self.0 % other.0)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        // NAN is the one number without a negative, so it stays NAN
+        Fixed::from_wide(-(self.0 as i128))
+    }
+}
+
+impl Real for Fixed {
+    const ZERO: Self = Fixed(0);
+    const ONE: Self = Fixed(ONE);
+    fn from_f64(n: f64) -> Self {
+        let raw = round(n * ONE as f64);
+        if raw.is_finite() && raw > i64::MIN as f64 && raw < i64::MAX as f64 {
+            Fixed(raw as i64)
+        } else {
+            Fixed::NAN
+        }
+    }
+    fn to_f64(self) -> f64 {
+        if self.is_nan() {
+            return f64::NAN;
+        }
+        self.0 as f64 / ONE as f64
+    }
+    fn from_constant(constant: Constant) -> Self {
+        constant.fast
+    }
+    fn pow(self, exponent: Self) -> Self {
+        if self.is_nan() || exponent.is_nan() {
+            return Fixed::NAN;
+        }
+        // other powers are 2^(exponent * log2(x)), which only works for positive x
+        if exponent.0 % ONE != 0 {
+            return match self.0 {
+                0 if exponent.0 > 0 => Fixed(0),
+                raw if raw > 0 => (exponent * self.log2()).exp2(),
+                _ => Fixed::NAN,
+            };
+        }
+        // whole powers are done by squaring, eg. x^5 is x^4 * x
+        let mut power = exponent.0 / ONE;
+        let mut base = if power < 0 { Fixed(ONE) / self } else { self };
+        power = power.abs();
+        let mut answer = Fixed(ONE);
+        while power > 0 && !answer.is_nan() {
+            if power & 1 == 1 {
+                answer = answer * base;
+            }
+            base = base * base;
+            power >>= 1;
+        }
+        answer
+    }
+    fn sqrt(self) -> Self {
+        if self.is_nan() || self.0 < 0 {
+            return Fixed::NAN;
+        }
+        // the integer square root of value * 2^40 is sqrt(value) * 2^20, found a bit at a time
+        let target = (self.0 as u128) << FRACTION_BITS;
+        if target == 0 {
+            return Fixed(0);
+        }
+        // starts at the biggest power of 4 that isn't more than target
+        let mut bit: u128 = 1 << ((127 - target.leading_zeros()) & !1);
+        let mut root: u128 = 0;
+        let mut rest = target;
+        while bit != 0 {
+            if rest >= root + bit {
+                rest -= root + bit;
+                root = (root >> 1) + bit;
+            } else {
+                root >>= 1;
+            }
+            bit >>= 2;
+        }
+        Fixed(root as i64)
+    }
+    fn sin(self) -> Self {
+        if self.is_nan() {
+            return Fixed::NAN;
+        }
+        // sin repeats every 2pi and is mirrored around pi/2, so only -pi/2..pi/2 needs the series
+        let mut x = self;
+        if x.abs() > PI {
+            x = Fixed(((((x.0 as i128) << 32) % TAU_WIDE) >> 32) as i64);
+        }
+        if x > PI {
+            x = x - TAU;
+        } else if x < -PI {
+            x = x + TAU;
+        }
+        if x > HALF_PI {
+            x = PI - x;
+        } else if x < -HALF_PI {
+            x = -PI - x;
+        }
+        // x - x^3/3! + x^5/5! - ..., worked out from the inside like x(1 - x^2/6(1 - x^2/20(...)))
+        let x2 = x * x;
+        let mut sum = Fixed(ONE);
+        for k in (1..=6).rev() {
+            sum = Fixed(ONE) - (x2 * sum).div_int(2 * k * (2 * k + 1));
+        }
+        x * sum
+    }
+    fn cos(self) -> Self {
+        (self + HALF_PI).sin()
+    }
+    fn tan(self) -> Self {
+        self.sin() / self.cos()
+    }
+    fn asin(self) -> Self {
+        if self.is_nan() || self.abs() > Fixed(ONE) {
+            return Fixed::NAN;
+        }
+        if self.abs() == Fixed(ONE) {
+            return if self.0 < 0 { -HALF_PI } else { HALF_PI };
+        }
+        (self / (Fixed(ONE) - self * self).sqrt()).atan()
+    }
+    fn acos(self) -> Self {
+        HALF_PI - self.asin()
+    }
+    fn atan(self) -> Self {
+        if self.is_nan() {
+            return Fixed::NAN;
+        }
+        // atan(-x) = -atan(x), atan(x) = pi/2 - atan(1/x) and atan(x) = pi/4 + atan((x-1)/(x+1)),
+        // which bring x under tan(pi/8) where the series is quick
+        let mut x = self.abs();
+        let inverted = x > Fixed(ONE);
+        if inverted {
+            x = Fixed(ONE) / x;
+        }
+        let shifted = x > TAN_EIGHTH_PI;
+        if shifted {
+            x = (x - Fixed(ONE)) / (x + Fixed(ONE));
+        }
+        // x - x^3/3 + x^5/5 - ...
+        let x2 = x * x;
+        let mut sum = Fixed::reciprocal(19);
+        for k in (0..9).rev() {
+            sum = Fixed::reciprocal(2 * k + 1) - x2 * sum;
+        }
+        let mut answer = x * sum;
+        if shifted {
+            answer = answer + QUARTER_PI;
+        }
+        if inverted {
+            answer = HALF_PI - answer;
+        }
+        if self.0 < 0 {
+            -answer
+        } else {
+            answer
+        }
+    }
+    fn ln(self) -> Self {
+        match self.split_log() {
+            Some((whole, ln_m)) => Fixed(whole * LN2.0) + ln_m,
+            None => Fixed::NAN,
+        }
+    }
+    fn log10(self) -> Self {
+        self.ln() / LN10
+    }
+}
+
+// a op b for the operators the solver works out
+pub fn operate<R: Real>(operator: &Token, a: R, b: R) -> R {
+    match operator {
+        Addition => a + b,
+        Subtraction => a - b,
+        Multiplication => a * b,
+        Division => a / b,
+        Modulation => a % b,
+        _ => a.pow(b),
+    }
+}
+
+impl CalcEngine {
+    // a op b in whichever number type the engine is set to, the answer is always given back as f64
+    // FAST mode only needs this for what the vm can't run, like percents, everything else stays fixed point
+    pub fn operate(&self, operator: &Token, a: f64, b: f64) -> f64 {
+        match self.number_mode {
+            NumberMode::Precise => operate(operator, a, b),
+            NumberMode::Fast => operate(operator, Fixed::from_f64(a), Fixed::from_f64(b)).to_f64(),
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use alloc::vec::Vec;
+use crate::token::Token::{self, *};
+
+use libm::{floor, tgamma};
+
+use super::{
+    bytecode::{compile, run},
+    core::CalcEngine,
+    math_function::FunctionHandling,
+    real::NumberMode,
+    uncertainty::fold_uncertain,
+};
+
+pub trait Solver {
+    fn solve(&mut self, equation: Vec<Token>) -> Result<Vec<Token>, &'static str>;
+    fn solve_rec(&mut self, token_list: &mut Vec<Token>) -> Result<Vec<Token>, &'static str>;
+}
+
+impl Solver for CalcEngine {
+    fn solve(&mut self, equation: Vec<Token>) -> Result<Vec<Token>, &'static str> {
+        if equation.is_empty() {
+            return Ok(Vec::from([Number(0.0)]));
+        }
+        let mut token_list = equation.clone();
+        // everything that uses the answer takes the first token, so there has to be one
+        match self.solve_rec(&mut token_list)? {
+            answer if answer.is_empty() => Err("Couldn't solve equation"),
+            answer => Ok(answer),
+        }
+    }
+
+    // each pass matches on its results so it can reset the index, from before the engine had its own crate
+    #[allow(clippy::question_mark)]
+    fn solve_rec(&mut self, token_list: &mut Vec<Token>) -> Result<Vec<Token>, &'static str> {
+        self.step()?;
+        if let Some(answer) = self.solve_fixed(token_list) {
+            return answer;
+        }
+        let mut i = 0;
+        while i < token_list.len() {
+            match &token_list[i] {
+                Token::LeftBracket => {
+                    let right_bracket_index = get_matching_bracket_index(&token_list[i + 1..]) + i + 1;
+                    match self.solve_rec(&mut token_list[i + 1..right_bracket_index].into()) {
+                        Ok(answer) if answer.is_empty() => return Err("nothing in the brackets"),
+                        Ok(answer) => token_list.splice(i..=right_bracket_index, answer),
+                        Err(e) => return Err(e),
+                    };
+                    i = 0;
+                }
+                Token::FunctionName(func_name) => {
+                    let right_bracket_index = get_matching_bracket_index(&token_list[i + 2..]) + i + 2;
+                    match self.solve_function(*func_name, &token_list[i + 2..right_bracket_index]) {
+                        Ok(answer) => token_list.splice(i..=right_bracket_index, Vec::from([answer])),
+                        Err(e) => return Err(e),
+                    };
+                    i = 0;
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+        // with the brackets and functions done, what's left might be something the vm can run now
+        if let Some(answer) = self.solve_fixed(token_list) {
+            return answer;
+        }
+        // a list can only be the whole answer, eg. seq(i,1,3,i)
+        if token_list.len() > 1 && token_list.iter().any(|t| matches!(t, Token::List(_))) {
+            return Err("lists can't be used with operators");
+        }
+        // +/- joins a number and its uncertainty first, so 2*3+/-0.1 is 2 times 3+/-0.1
+        // a percent after it is of the number, eg. 50+/-10% is 50+/-5
+        i = 0;
+        while i < token_list.len() {
+            if token_list[i] == Token::PlusMinus {
+                fold_negation(token_list, i + 1);
+                match (&token_list[i - 1], &token_list[i + 1]) {
+                    (Token::Number(value), Token::Number(uncertainty)) => {
+                        let value = *value;
+                        let uncertainty = take_percent(token_list, i + 2, value, *uncertainty);
+                        token_list[i - 1] = Token::Uncertain(value, uncertainty.abs());
+                        token_list.remove(i);
+                        token_list.remove(i);
+                    }
+                    _ => return Err("+/- needs a number on each side"),
+                }
+            } else {
+                i += 1;
+            }
+        }
+        // postfix operators bind the tightest, eg. 2^3! is 2^6
+        i = 0;
+        while i < token_list.len() {
+            match token_list[i] {
+                Token::Factorial => {
+                    if let Token::Uncertain(..) = token_list[i - 1] {
+                        return Err("+/- values can't use !");
+                    }
+                    if let Token::Number(n) = token_list[i - 1] {
+                        match factorial(n) {
+                            Ok(answer) => token_list[i - 1] = Token::Number(answer),
+                            Err(e) => return Err(e),
+                        }
+                        token_list.remove(i);
+                        i = 0;
+                    } else {
+                        i += 1;
+                    }
+                }
+                Token::Percent => {
+                    if matches!(token_list[i - 1], Token::Number(_) | Token::Uncertain(..)) && is_added_percent(token_list, i) {
+                        // left for the addition pass, which knows what it is a percent of
+                        i += 1;
+                    } else if let Token::Uncertain(value, uncertainty) = token_list[i - 1] {
+                        token_list[i - 1] = Token::Uncertain(value / 100.0, uncertainty / 100.0);
+                        token_list.remove(i);
+                        i = 0;
+                    } else if let Token::Number(n) = token_list[i - 1] {
+                        token_list[i - 1] = Token::Number(n / 100.0);
+                        token_list.remove(i);
+                        i = 0;
+                    } else {
+                        i += 1;
+                    }
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+        // a sign directly after a '^' belongs to the exponent, eg. 2^-3
+        i = 0;
+        while i < token_list.len() {
+            if token_list[i] == Token::Exponentation {
+                fold_negation(token_list, i + 1);
+            }
+            i += 1;
+        }
+        i = 0;
+        while i < token_list.len() {
+            if token_list[i] == Token::Exponentation && fold_uncertain(token_list, i, self.uncertainty_mode)? {
+                i = 0;
+                continue;
+            }
+            match token_list[i] {
+                Token::Exponentation => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let answer = self.operate(&Token::Exponentation, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+        // unary '-' binds looser than '^', so -2^2 is -4
+        i = 0;
+        while i < token_list.len() {
+            fold_negation(token_list, i);
+            i += 1;
+        }
+        i = 0;
+        while i < token_list.len() {
+            if matches!(token_list[i], Token::Multiplication | Token::Division | Token::Modulation)
+                && fold_uncertain(token_list, i, self.uncertainty_mode)?
+            {
+                i = 0;
+                continue;
+            }
+            match token_list[i] {
+                Token::Multiplication => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let answer = self.operate(&Token::Multiplication, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                Token::Division => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let answer = self.operate(&Token::Division, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                Token::Modulation => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let answer = self.operate(&Token::Modulation, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+        i = 0;
+        while i < token_list.len() {
+            if matches!(token_list[i], Token::Addition | Token::Subtraction)
+                && fold_uncertain(token_list, i, self.uncertainty_mode)?
+            {
+                i = 0;
+                continue;
+            }
+            match token_list[i] {
+                Token::Addition => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let b = take_percent(token_list, i + 2, a, b);
+                            let answer = self.operate(&Token::Addition, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                Token::Subtraction => {
+                    if let Token::Number(a) = token_list[i - 1] {
+                        if let Token::Number(b) = token_list[i + 1] {
+                            let b = take_percent(token_list, i + 2, a, b);
+                            let answer = self.operate(&Token::Subtraction, a, b);
+                            token_list[i - 1] = Token::Number(answer);
+                            token_list.remove(i);
+                            token_list.remove(i);
+                            i = 0;
+                        }
+                    }
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+        // comparisons come last, true is 1 and false is 0
+        i = 0;
+        while i < token_list.len() {
+            let compare: Option<fn(f64, f64) -> bool> = match token_list[i] {
+                Token::Less => Some(|a, b| a < b),
+                Token::Greater => Some(|a, b| a > b),
+                Token::LessEqual => Some(|a, b| a <= b),
+                Token::GreaterEqual => Some(|a, b| a >= b),
+                Token::Equal => Some(|a, b| a == b),
+                Token::NotEqual => Some(|a, b| a != b),
+                _ => None,
+            };
+            if compare.is_some() && fold_uncertain(token_list, i, self.uncertainty_mode)? {
+                continue;
+            }
+            match compare {
+                Some(compare) => {
+                    if let (Token::Number(a), Token::Number(b)) = (&token_list[i - 1], &token_list[i + 1]) {
+                        let answer = if compare(*a, *b) { 1.0 } else { 0.0 };
+                        token_list[i - 1] = Token::Number(answer);
+                        token_list.remove(i);
+                        token_list.remove(i);
+                        i = 0;
+                    } else {
+                        i += 1;
+                    }
+                }
+                None => {
+                    i += 1;
+                }
+            }
+        }
+    
+        Ok(token_list.to_vec())
+    }
+}
+
+impl CalcEngine {
+    // FAST mode runs anything the vm can, so numbers stay fixed point between operators instead of going back to f64
+    fn solve_fixed(&mut self, token_list: &[Token]) -> Option<Result<Vec<Token>, &'static str>> {
+        if self.number_mode != NumberMode::Fast {
+            return None;
+        }
+        let code = compile(token_list, &self.builtins)?;
+        Some(run(&code, &[], self.angle_mode(), self.number_mode).map(|answer| Vec::from([Number(answer)])))
+    }
+}
+
+// n! for whole numbers, and the gamma function for everything else
+pub fn factorial(n: f64) -> Result<f64, &'static str> {
+    if n != floor(n) {
+        return Ok(tgamma(n + 1.0));
+    }
+    if n < 0.0 {
+        return Err("factorial of negative integer");
+    }
+    let mut answer: f64 = 1.0;
+    let mut k = 2.0;
+    while k <= n && answer.is_finite() {
+        answer *= k;
+        k += 1.0;
+    }
+    Ok(answer)
+}
+
+// checks if the percent at index is being added to or subtracted from something, eg. 200 + 10%
+fn is_added_percent(token_list: &[Token], index: usize) -> bool {
+    let mut prev = index - 1;
+    while prev > 0 && token_list[prev - 1] == Token::Negation {
+        prev -= 1;
+    }
+    let is_added = prev > 0
+        && (token_list[prev - 1] == Token::Addition || token_list[prev - 1] == Token::Subtraction);
+    let is_whole_term = matches!(token_list.get(index + 1), None | Some(Token::Addition) | Some(Token::Subtraction));
+    is_added && is_whole_term
+}
+
+// if there is a percent at index, removes it and returns that percent of base, otherwise returns n
+fn take_percent(token_list: &mut Vec<Token>, index: usize, base: f64, n: f64) -> f64 {
+    if token_list.get(index) == Some(&Token::Percent) {
+        token_list.remove(index);
+        base * n / 100.0
+    } else {
+        n
+    }
+}
+
+// turns a run of unary '-' starting at index into the signed number (or +/- value) that follows it
+// does nothing if there is no '-' at index, or if the run isn't followed by a number
+fn fold_negation(token_list: &mut Vec<Token>, index: usize) {
+    let mut end = index;
+    while end < token_list.len() && token_list[end] == Token::Negation {
+        end += 1;
+    }
+    if end == index || end >= token_list.len() {
+        return;
+    }
+    let sign = if (end - index) % 2 == 0 { 1.0 } else { -1.0 };
+    match token_list[end] {
+        Token::Number(n) => {
+            token_list.splice(index..=end, Vec::from([Token::Number(sign * n)]));
+        }
+        Token::Uncertain(value, uncertainty) => {
+            token_list.splice(index..=end, Vec::from([Token::Uncertain(sign * value, uncertainty)]));
+        }
+        _ => (),
+    }
+}
+
+// splits a list of tokens at the commas that aren't inside brackets, eg. the arguments of a function
+pub fn split_arguments(tokens: &[Token]) -> Vec<Vec<Token>> {
+    let mut parts: Vec<Vec<Token>> = Vec::new();
+    let mut part: Vec<Token> = Vec::new();
+    let mut depth = 0;
+    for token in tokens {
+        match token {
+            Token::LeftBracket => depth += 1,
+            Token::RightBracket => depth -= 1,
+            Token::Comma if depth == 0 => {
+                parts.push(part);
+                part = Vec::new();
+                continue;
+            }
+            _ => (),
+        }
+        part.push(token.clone());
+    }
+    parts.push(part);
+    parts
+}
+
+pub fn get_matching_bracket_index(token_list: &[Token]) -> usize {
+    let mut depth = 1;
+    for (i, token) in token_list.iter().enumerate() {
+        match token {
+            Token::LeftBracket => depth += 1,
+            Token::RightBracket => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return i;
+        }
+    }
+
+    0
+}
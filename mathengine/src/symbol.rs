@@ -0,0 +1,103 @@
+use core::mem::size_of;
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+// a name that has been interned, so it can be copied and compared without touching its bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+// every name the engine has seen, each one is only stored once
+pub struct Interner {
+    names: Vec<Vec<u8>>,
+    ids: BTreeMap<Vec<u8>, Symbol>,
+    // symbols whose names were freed, given to the next new names
+    free: Vec<Symbol>,
+    // kept up to date as names come and go, so size doesn't have to go through every name
+    bytes: usize,
+}
+
+impl Default for Interner {
+    fn default() -> Interner {
+        Interner::new()
+    }
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner {
+            names: Vec::new(),
+            ids: BTreeMap::new(),
+            free: Vec::new(),
+            bytes: 0,
+        }
+    }
+
+    // the symbol for name, which is added if it hasn't been seen before
+    pub fn intern(&mut self, name: &[u8]) -> Symbol {
+        if let Some(symbol) = self.ids.get(name) {
+            return *symbol;
+        }
+        let symbol = match self.free.pop() {
+            Some(symbol) => {
+                self.names[symbol.0 as usize] = name.to_vec();
+                self.bytes -= size_of::<Symbol>();
+                symbol
+            }
+            None => {
+                self.names.push(name.to_vec());
+                self.bytes += size_of::<Vec<u8>>();
+                Symbol(self.names.len() as u32 - 1)
+            }
+        };
+        self.ids.insert(name.to_vec(), symbol);
+        self.bytes += lookup_size(name) + name.len();
+        symbol
+    }
+
+    // frees every name that in_use says nothing has a symbol for any more
+    pub fn retain<F>(&mut self, in_use: F)
+    where
+        F: Fn(Symbol) -> bool,
+    {
+        let names = &mut self.names;
+        let free = &mut self.free;
+        let bytes = &mut self.bytes;
+        self.ids.retain(|name, symbol| {
+            if in_use(*symbol) {
+                return true;
+            }
+            names[symbol.0 as usize] = Vec::new();
+            free.push(*symbol);
+            *bytes = *bytes + size_of::<Symbol>() - lookup_size(name) - name.len();
+            false
+        });
+    }
+
+    // how many names there are
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    // roughly how many bytes the names take up, each is kept twice so it can be looked up either way
+    pub fn size(&self) -> usize {
+        self.bytes
+    }
+
+    // the symbol for name, without adding it
+    pub fn lookup(&self, name: &[u8]) -> Option<Symbol> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, symbol: Symbol) -> &[u8] {
+        &self.names[symbol.0 as usize]
+    }
+}
+
+// an entry in the map from names to symbols
+fn lookup_size(name: &[u8]) -> usize {
+    name.len() + size_of::<Vec<u8>>() + size_of::<Symbol>()
+}
@@ -0,0 +1,306 @@
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+
+use crate::{core::CalcEngine, token::Token::*};
+
+use super::{
+    builtin::BuiltinKind,
+    manage::RESERVED_WORDS,
+    solve::get_matching_bracket_index,
+    symbol::{Interner, Symbol},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Addition,       //              +
+    Subtraction,    //           -
+    Division,       //              /
+    Multiplication, //        *
+    Truncation,     //            //
+    Modulation,     //            %
+    Exponentation,  //         **
+    Negation,       //            - (unary)
+    Factorial,      //            !
+    Percent,        //            % (postfix)
+    PlusMinus,      //            +/-
+
+    Less,         //           <
+    Greater,      //           >
+    LessEqual,    //           <=
+    GreaterEqual, //           >=
+    Equal,        //           ==
+    NotEqual,     //           !=
+
+    BitwiseLeftShift,  //      <<
+    BitwiseRightShift, //     >>
+    BitwiseAnd,        //            &
+    BitwiseOr,         //             |
+    BitwiseXor,        //            ^
+
+    LeftBracket,  //           (
+    RightBracket, //          )
+
+    Comma, //                 ,
+    Semicolon, //             ;
+
+    Assignment, //            =
+    Definition, //            :=
+
+    Let, //                   let
+
+    UnresolvedString(Symbol),
+    Variable(Symbol),
+    FunctionName(Symbol),
+    FunctionArg(usize),
+
+    Number(f64),
+    // a list of numbers, made by seq
+    List(Vec<f64>),
+    // a value and its uncertainty, made with +/-
+    Uncertain(f64, f64),
+}
+
+pub fn match_token(key: &[u8]) -> Option<Token> {
+    match key {
+        b"<<" => Some(BitwiseLeftShift),
+        b">>" => Some(BitwiseRightShift),
+        b"^" => Some(Exponentation),
+        b"//" => Some(Truncation),
+        b"+/-" => Some(PlusMinus),
+        b"+" => Some(Addition),
+        b"-" => Some(Subtraction),
+        b"/" => Some(Division),
+        b"*" => Some(Multiplication),
+        b"%" => Some(Modulation),
+        b"!" => Some(Factorial),
+        b"<" => Some(Less),
+        b">" => Some(Greater),
+        b"<=" => Some(LessEqual),
+        b">=" => Some(GreaterEqual),
+        b"==" => Some(Equal),
+        b"!=" => Some(NotEqual),
+        b"&" => Some(BitwiseAnd),
+        b"|" => Some(BitwiseOr),
+        b"^^" => Some(BitwiseXor),
+        b"(" => Some(LeftBracket),
+        b")" => Some(RightBracket),
+        b"," => Some(Comma),
+        b";" => Some(Semicolon),
+        b"=" => Some(Assignment),
+        b":=" => Some(Definition),
+        _ => None,
+    }
+}
+
+// names are interned into symbols, so they only get stored once
+pub fn tokenize(input: &[u8], symbols: &mut Interner) -> Result<Vec<Token>, &'static str> {
+    let mut tokens = get_tokens(input, symbols)?;
+    resolve_strings(&mut tokens);
+    Ok(tokens)
+}
+
+fn get_tokens(input: &[u8], symbols: &mut Interner) -> Result<Vec<Token>, &'static str> {
+    // this will store the list of tokens, and will be returned
+    let mut tokens: Vec<Token> = Vec::new();
+
+    let is_number_part = |x: u8| x.is_ascii_digit() || x == b'.';
+    let is_string_part = |x: u8| (x as char).is_alphabetic() || x == b'_';
+    // names can have digits in them after the first letter, eg. ans2
+    let is_name_part = |x: u8| is_string_part(x) || x.is_ascii_digit();
+
+    // this loop parses the input bytes into a vec of raw tokens
+    let mut i: usize = 0;
+    let len = input.len();
+    'outer: 
+    while i < len {
+        // spaces just seperate tokens, eg. let x
+        if input[i] == b' ' {
+            i += 1;
+        }
+        // numbers
+        else if is_number_part(input[i]) {
+            let mut slice_bounds = get_token_bounds(is_number_part, i, input);
+            // scientific notation, eg. 6.02E23 or 1E-9
+            if slice_bounds.1 < len && input[slice_bounds.1] == b'E' {
+                let mut exponent_start = slice_bounds.1 + 1;
+                if exponent_start < len && (input[exponent_start] == b'-' || input[exponent_start] == b'+') {
+                    exponent_start += 1;
+                }
+                if exponent_start < len && input[exponent_start].is_ascii_digit() {
+                    slice_bounds.1 = get_token_bounds(|x| x.is_ascii_digit(), exponent_start, input).1;
+                }
+            }
+            match String::from_utf8(input[slice_bounds.0..slice_bounds.1].to_vec()).unwrap().parse::<f64>() {
+                Ok(n) => {
+                    tokens.push(Number(n));
+                }
+                Err(_) => return Err("unable to parse number"),
+            }
+            i = slice_bounds.1;
+        }
+        // strings
+        else if is_string_part(input[i]) {
+            let slice_bounds: (usize, usize) = get_token_bounds(is_name_part, i, input);
+            // just stored as a string for now, will later be turned into a variable, function, or command
+            let name = &input[slice_bounds.0..slice_bounds.1];
+            if name == b"let" {
+                tokens.push(Let);
+            } else if RESERVED_WORDS.contains(&name) {
+                // otherwise a variable called list couldn't be looked at, typing it would list everything
+                // and a program line like end=1 would be read as a keyword
+                return Err("that name is reserved");
+            } else {
+                tokens.push(UnresolvedString(symbols.intern(name)));
+            }
+            i = slice_bounds.1;
+        } else if input[i].is_ascii() {
+            //operator token, the longest is +/-
+            for j in (0..4).rev() {
+                if i + j <= len {
+                    if let Some(t) = match_token(&input[i..i + j]) {
+                        tokens.push(t.clone());
+                        i += j;
+                        continue 'outer;
+                    }
+                }
+            }
+            return Err("unknown operator");
+        } else {
+            return Err("invalid input");
+        }
+    }
+
+    
+    
+
+    // handle percents
+    // a '%' is a percent instead of a modulo if nothing that could be its right hand side follows it
+    // a sign followed by something to take it is a right hand side, eg. 10%-3 is a modulo
+    for i in 0..tokens.len() {
+        if tokens[i] == Modulation {
+            let signed_operand = matches!(tokens.get(i + 1), Some(Subtraction | Addition))
+                && matches!(tokens.get(i + 2), Some(Number(_) | UnresolvedString(_) | LeftBracket));
+            if signed_operand {
+                continue;
+            }
+            match tokens.get(i + 1) {
+                None
+                | Some(crate::operators!())
+                | Some(Factorial)
+                | Some(RightBracket)
+                | Some(Comma)
+                | Some(Semicolon) => tokens[i] = Percent,
+                _ => (),
+            }
+        }
+    }
+
+    // handle signs (negative, positive)
+    // a '+' or '-' is a sign if it starts the expression, or comes after an operator, '(' or ','
+    let mut i: usize = 0;
+    while i < tokens.len() {
+        let is_sign = i == 0
+            || matches!(
+                tokens[i - 1],
+                crate::operators!() | Negation | LeftBracket | Comma | Semicolon
+            );
+        match tokens[i] {
+            Subtraction if is_sign => {
+                tokens[i] = Negation;
+                i += 1;
+            }
+            Addition if is_sign => {
+                // a positive sign does nothing, so just drop it
+                tokens.remove(i);
+            }
+            _ => {
+                i += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn resolve_strings(tokens: &mut [Token]) {
+    for i in 0..tokens.len() {
+        if let UnresolvedString(name) = tokens[i] {
+            match tokens.get(i + 1) {
+                Some(LeftBracket) => tokens[i] = FunctionName(name),
+                _ => tokens[i] = Variable(name),
+            }
+        }
+    }
+}
+
+pub trait TokenHandling {
+    fn resolve_variables(&self, tokens: &mut Vec<Token>) -> Option<&'static str>; 
+}
+
+impl TokenHandling for CalcEngine {
+    fn resolve_variables(&self, tokens: &mut Vec<Token>) -> Option<&'static str> {
+        // index variables of sum, prod and seq, and where their call ends
+        // they get a value when the call is solved, so they are left alone inside it
+        let mut bound: Vec<(Symbol, usize)> = Vec::new();
+        for i in 0..tokens.len() {
+            bound.retain(|(_, end)| *end > i);
+            if let (FunctionName(func_name), Some(Variable(index))) = (&tokens[i], tokens.get(i + 2)) {
+                if matches!(self.builtins.kind(*func_name), Some(BuiltinKind::Bound(_))) {
+                    let end = get_matching_bracket_index(&tokens[i + 2..]) + i + 2;
+                    bound.push((*index, end));
+                }
+            }
+            // a function name passed to ode isn't a variable either
+            if i >= 2 && tokens.get(i + 1) == Some(&Comma) {
+                if let (FunctionName(func_name), LeftBracket) = (&tokens[i - 2], &tokens[i - 1]) {
+                    if matches!(self.builtins.kind(*func_name), Some(BuiltinKind::Ode(_))) {
+                        continue;
+                    }
+                }
+            }
+
+            let name = match tokens[i] {
+                Variable(name) => name,
+                _ => continue,
+            };
+            if bound.iter().any(|(index, _)| *index == name) {
+                continue;
+            }
+            if let Some(local) = self.locals.get(&name) {
+                tokens[i] = local.clone();
+                continue;
+            }
+            if let Some(answer) = self.get_answer(name) {
+                tokens[i] = answer.clone();
+                continue;
+            }
+            let hash_try = self.variables.get(&name);
+            match hash_try {
+                Some(number) => {
+                    tokens[i] = number.clone();
+                }
+                None => {
+                    return Some("Variable does not exist");
+                }
+            }
+        }
+        None
+    }
+
+    
+}
+
+fn get_token_bounds<F>(f: F, start: usize, input_chars: &[u8]) -> (usize, usize)
+where
+    F: Fn(u8) -> bool,
+{
+    let mut end: usize = start + 1;
+
+    while end < input_chars.len() && f(input_chars[end]) {
+        end += 1;
+    }
+
+    (start, end)
+}
@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use libm::{ceil, fabs, floor, hypot, log, pow, trunc};
+
+use crate::token::Token::{self, *};
+
+use super::{
+    builtin::{Builtin, BuiltinKind, Unary},
+    core::CalcEngine,
+};
+
+// how a +/- is carried through the math
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UncertaintyMode {
+    // the +/- is a standard deviation, and each step adds up the errors of its inputs to first order
+    // every input counts as independent, so x - x isn't exactly 0
+    Gaussian,
+    // the +/- is a hard bound, the answer is guaranteed to hold everything the inputs could be
+    Interval,
+}
+
+impl UncertaintyMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UncertaintyMode::Gaussian => "GAUSS",
+            UncertaintyMode::Interval => "INTVL",
+        }
+    }
+}
+
+// a value and its +/-, in interval mode the +/- is half the width of the interval
+type Measurement = (f64, f64);
+
+// a number or a +/- value as a pair, or None if it's something else
+fn as_uncertain(token: Option<&Token>) -> Option<Measurement> {
+    match token {
+        Some(Number(n)) => Some((*n, 0.0)),
+        Some(Uncertain(value, uncertainty)) => Some((*value, *uncertainty)),
+        _ => None,
+    }
+}
+
+// if the operator at index has a +/- value on either side, works it out and returns true
+pub fn fold_uncertain(token_list: &mut Vec<Token>, index: usize, mode: UncertaintyMode) -> Result<bool, &'static str> {
+    if index == 0 {
+        return Ok(false);
+    }
+    let has_uncertain = matches!(token_list[index - 1], Uncertain(..)) || matches!(token_list.get(index + 1), Some(Uncertain(..)));
+    let (a, b) = match (as_uncertain(token_list.get(index - 1)), as_uncertain(token_list.get(index + 1))) {
+        (Some(a), Some(b)) if has_uncertain => (a, b),
+        _ => return Ok(false),
+    };
+    // adding a percent is a percent of a, so a + b% is a * (1 + b/100) and both sides keep a's +/-
+    let mut end = index + 1;
+    let (operator, b) = match token_list[index] {
+        Addition | Subtraction if token_list.get(index + 2) == Some(&Percent) => {
+            end += 1;
+            let sign = if token_list[index] == Addition { 1.0 } else { -1.0 };
+            (Multiplication, (1.0 + sign * b.0 / 100.0, b.1 / 100.0))
+        }
+        ref operator => (operator.clone(), b),
+    };
+    let answer = match mode {
+        UncertaintyMode::Gaussian => gaussian_operator(&operator, a, b)?,
+        UncertaintyMode::Interval => interval_operator(&operator, a, b)?,
+    };
+    if !answer.0.is_finite() || !answer.1.is_finite() {
+        return Err("+/- went out of range");
+    }
+    token_list.splice(index - 1..=end, Vec::from([Uncertain(answer.0, answer.1)]));
+    Ok(true)
+}
+
+// first order error propagation, the +/- of f(a, b) is the sum in quadrature of each input's +/- times how much f changes with it
+fn gaussian_operator(operator: &Token, (a, da): Measurement, (b, db): Measurement) -> Result<Measurement, &'static str> {
+    let answer = match operator {
+        Addition => (a + b, hypot(da, db)),
+        Subtraction => (a - b, hypot(da, db)),
+        Multiplication => (a * b, hypot(b * da, a * db)),
+        Division => (a / b, hypot(da / b, a * db / (b * b))),
+        Modulation => (a % b, hypot(da, trunc(a / b) * db)),
+        Exponentation => {
+            // the exponent's +/- only matters if it has one, and then the base can't be negative
+            let exponent_part = if db == 0.0 {
+                0.0
+            } else if a > 0.0 {
+                pow(a, b) * log(a) * db
+            } else {
+                return Err("+/- powers need a positive base");
+            };
+            let base_part = if da == 0.0 { 0.0 } else { b * pow(a, b - 1.0) * da };
+            (pow(a, b), hypot(base_part, exponent_part))
+        }
+        _ => return Err("+/- values can't be compared"),
+    };
+    Ok(answer)
+}
+
+// interval arithmetic on the lowest and highest each side could be
+fn interval_operator(operator: &Token, (a, da): Measurement, (b, db): Measurement) -> Result<Measurement, &'static str> {
+    let (a_low, a_high) = (a - da, a + da);
+    let (b_low, b_high) = (b - db, b + db);
+    let corners = |f: fn(f64, f64) -> f64| [f(a_low, b_low), f(a_low, b_high), f(a_high, b_low), f(a_high, b_high)];
+    let bounds = match operator {
+        Addition => (a_low + b_low, a_high + b_high),
+        Subtraction => (a_low - b_high, a_high - b_low),
+        Multiplication => min_max(&corners(|x, y| x * y)),
+        Division => {
+            if b_low <= 0.0 && b_high >= 0.0 {
+                return Err("can't divide by an interval with 0 in it");
+            }
+            min_max(&corners(|x, y| x / y))
+        }
+        Exponentation => {
+            if a_low > 0.0 {
+                // x^y is e^(y ln x), so the ends are always at the corners
+                min_max(&corners(pow))
+            } else if db == 0.0 && b == floor(b) {
+                // whole powers of a base that can be negative, which only turns around at 0
+                let mut values = Vec::from([pow(a_low, b), pow(a_high, b)]);
+                if a_low <= 0.0 && a_high >= 0.0 {
+                    if b < 0.0 {
+                        return Err("can't divide by an interval with 0 in it");
+                    }
+                    values.push(pow(0.0, b));
+                }
+                min_max(&values)
+            } else {
+                return Err("+/- powers need a positive base");
+            }
+        }
+        Modulation => return Err("% doesn't work on intervals"),
+        _ => return Err("+/- values can't be compared"),
+    };
+    Ok(from_bounds(bounds))
+}
+
+fn min_max(values: &[f64]) -> (f64, f64) {
+    values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), x| (low.min(*x), high.max(*x)))
+}
+
+fn from_bounds((low, high): (f64, f64)) -> Measurement {
+    ((low + high) / 2.0, (high - low) / 2.0)
+}
+
+impl CalcEngine {
+    // a built in function like sin or sqrt of a +/- value
+    pub fn solve_uncertain_builtin(&self, builtin: Builtin, (x, dx): Measurement) -> Result<Token, &'static str> {
+        let f = |x: f64| self.solve_builtin(&builtin, x).unwrap_or(f64::NAN);
+        let answer = match self.uncertainty_mode {
+            UncertaintyMode::Gaussian => {
+                // how much f changes with x, from a small step either side
+                let h = 1e-6 * fabs(x).max(1.0);
+                let slope = (f(x + h) - f(x - h)) / (2.0 * h);
+                (f(x), fabs(slope) * dx)
+            }
+            UncertaintyMode::Interval => {
+                let (low, high) = (x - dx, x + dx);
+                // where the function turns around or jumps, as the first one and how far apart they are, in radians
+                let angle_mode = if self.use_radians { 1.0 } else { PI / 180.0 };
+                let (turns, jumps) = match builtin.kind {
+                    BuiltinKind::Unary(Unary::Sin) => (Some(PI / 2.0), None),
+                    BuiltinKind::Unary(Unary::Cos) => (Some(0.0), None),
+                    BuiltinKind::Unary(Unary::Tan) => (None, Some(PI / 2.0)),
+                    _ => (None, None),
+                };
+                if let Some(first) = jumps {
+                    if !points_between(low * angle_mode, high * angle_mode, first, PI).is_empty() {
+                        return Err("tan isn't continuous over the interval");
+                    }
+                }
+                let mut values = Vec::from([f(low), f(high)]);
+                if let Some(first) = turns {
+                    for point in points_between(low * angle_mode, high * angle_mode, first, PI) {
+                        values.push(f(point / angle_mode));
+                    }
+                }
+                if values.iter().any(|v| !v.is_finite()) {
+                    return Err("+/- goes outside what the function takes");
+                }
+                from_bounds(min_max(&values))
+            }
+        };
+        if !answer.0.is_finite() || !answer.1.is_finite() {
+            return Err("+/- goes outside what the function takes");
+        }
+        Ok(Uncertain(answer.0, answer.1))
+    }
+}
+
+// the points first + k*spacing from low to high, only the first couple since after that every value has been hit
+fn points_between(low: f64, high: f64, first: f64, spacing: f64) -> Vec<f64> {
+    let mut points: Vec<f64> = Vec::new();
+    let mut k = ceil((low - first) / spacing);
+    while first + k * spacing <= high && points.len() < 3 {
+        points.push(first + k * spacing);
+        k += 1.0;
+    }
+    points
+}
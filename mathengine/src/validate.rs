@@ -1,13 +1,13 @@
-use agb::println;
-use alloc::vec::Vec;
+use crate::token::Token::{self, *};
 
-use crate::mathengine::token::Token::{self, *};
+use super::builtin::Registry;
 
 // just all of the operator tokens as a macro so I dont have to type all of them every time
 #[macro_export]
 macro_rules! operators {
     () => {
         Assignment
+            | Definition
             | Addition
             | Subtraction
             | Division
@@ -15,6 +15,13 @@ macro_rules! operators {
             | Truncation
             | Modulation
             | Exponentation
+            | PlusMinus
+            | Less
+            | Greater
+            | LessEqual
+            | GreaterEqual
+            | Equal
+            | NotEqual
             | BitwiseLeftShift
             | BitwiseRightShift
             | BitwiseAnd
@@ -23,16 +30,43 @@ macro_rules! operators {
     };
 }
 
-pub fn validate_token_list(tokens: &Vec<Token>) -> Option<&'static str> {
-    // make sure input is not empty
-    if tokens.len() == 0 {
-        return Some("")
+pub fn validate_token_list(tokens: &[Token]) -> Option<&'static str> {
+    // make sure there is something to work out, a lone '+' or ';' has nothing
+    if tokens.iter().all(|t| t == &Semicolon) {
+        return Some("missing expression")
     }
 
-    // make sure there are one or less '='
+    // each statement between ';'s is checked on its own, empty ones are skipped
+    for statement in tokens.split(|t| t == &Semicolon) {
+        if statement.is_empty() {
+            continue;
+        }
+        if let Some(e) = validate_statement(statement) {
+            return Some(e);
+        }
+    }
+
+    None
+}
+
+fn validate_statement(tokens: &[Token]) -> Option<&'static str> {
+    // a let has to be 'let name = ...', after that its checked like any other assignment
+    let tokens = if tokens[0] == Let {
+        if tokens.len() < 3 || !matches!(tokens[1], Variable(_)) || tokens[2] != Assignment {
+            return Some("let needs a variable and a '='");
+        }
+        &tokens[1..]
+    } else {
+        tokens
+    };
+    if tokens.contains(&Let) {
+        return Some("let must start a statement");
+    }
+
+    // make sure there are one or less '=' (or ':=')
     let mut equal_count = 0;
     for token in tokens {
-        if token == &Assignment {
+        if token == &Assignment || token == &Definition {
             equal_count += 1;
         }
     }
@@ -42,11 +76,15 @@ pub fn validate_token_list(tokens: &Vec<Token>) -> Option<&'static str> {
 
     // make sure first and last tokens are valid
     match tokens[0] {
-        Comma | RightBracket | operators!() => return Some("invalid first token"),
+        Comma | RightBracket | Factorial | Percent | operators!() => {
+            return Some("invalid first token")
+        }
         _ => (),
     }
     match tokens[tokens.len() - 1] {
-        Comma | LeftBracket | FunctionName(_) | operators!() => return Some("invalid last token"),
+        Comma | LeftBracket | FunctionName(_) | Negation | operators!() => {
+            return Some("invalid last token")
+        }
         _ => (),
     }
 
@@ -91,10 +129,8 @@ pub fn validate_token_list(tokens: &Vec<Token>) -> Option<&'static str> {
                     }
                 }
             }
-            Comma => {
-                if !in_function_brackets {
-                    return Some("commas only go in functions");
-                }
+            Comma if !in_function_brackets => {
+                return Some("commas only go in functions");
             }
             _ => {}
         }
@@ -104,6 +140,8 @@ pub fn validate_token_list(tokens: &Vec<Token>) -> Option<&'static str> {
     None
 }
 
+// every case returns explicitly so the table of invalid pairs reads the same all the way down
+#[allow(clippy::needless_return)]
 fn is_next_token_valid(current: &Token, next: &Token) -> bool {
     match current {
         // current token is a number or var
@@ -123,6 +161,19 @@ fn is_next_token_valid(current: &Token, next: &Token) -> bool {
             // if next token matches any of these, its invalid
             match next {
                 operators!() => return false,
+                Factorial | Percent => return false,
+                RightBracket => return false,
+                Comma => return false,
+                _ => return true,
+            };
+        }
+
+        // current token is a unary '-'
+        Negation => {
+            // if next token matches any of these, its invalid
+            match next {
+                operators!() => return false,
+                Factorial | Percent => return false,
                 RightBracket => return false,
                 Comma => return false,
                 _ => return true,
@@ -134,13 +185,14 @@ fn is_next_token_valid(current: &Token, next: &Token) -> bool {
             // if next token matches any of these, its invalid
             match next {
                 operators!() => return false,
+                Factorial | Percent => return false,
                 Comma => return false,
                 _ => return true,
             };
         }
 
-        // current token is a ')'
-        RightBracket => {
+        // current token is a ')', or a postfix operator like '!'
+        RightBracket | Factorial | Percent => {
             // if next token matches any of these, its invalid
             match next {
                 Number(_) => return false,
@@ -165,6 +217,7 @@ fn is_next_token_valid(current: &Token, next: &Token) -> bool {
             // if next token matches any of these, its invalid
             match next {
                 operators!() => return false,
+                Factorial | Percent => return false,
                 Comma => return false,
                 RightBracket => return false,
                 _ => return true,
@@ -176,33 +229,22 @@ fn is_next_token_valid(current: &Token, next: &Token) -> bool {
 
 }
 
-pub fn is_valid_lhs_function(tokens: &Vec<Token>) -> Option<&'static str> {
-    println!("{:?}", tokens);
+pub fn is_valid_lhs_function(tokens: &[Token], builtins: &Registry) -> Option<&'static str> {
     // make sure first token is a function name that is not reserved
     match &tokens[0] {
         FunctionName(name) => {
-            match &name[..] {
-                b"sin" => return Some("sin() cannot be reassigned"),
-                b"cos" => return Some("cos() cannot be reassigned"),
-                b"tan" => return Some("tan() cannot be reassigned"),
-                b"asin" => return Some("asin() cannot be reassigned"),
-                b"acos" => return Some("acos() cannot be reassigned"),
-                b"atan" => return Some("atan() cannot be reassigned"),
-                b"sqrt" => return Some("sqrt() cannot be reassigned"),
-                b"fact" => return Some("fact() cannot be reassigned"),
-                b"log" => return Some("log() cannot be reassigned"),
-                b"ln" => return Some("ln() cannot be reassigned"),
-                _ => ()
+            if builtins.get(*name).is_some() {
+                return Some("built in functions cannot be reassigned");
             }
         }
         _ => return Some("Not a function"),
     }
 
     // args must be encased in brackets
-    if &tokens[1] != &LeftBracket {
+    if tokens[1] != LeftBracket {
         return Some("func args must be in brackets");
     }
-    if &tokens[tokens.len()-1] != &RightBracket {
+    if tokens[tokens.len()-1] != RightBracket {
         return Some("func args must be in brackets");
     }
 
@@ -221,7 +263,7 @@ pub fn is_valid_lhs_function(tokens: &Vec<Token>) -> Option<&'static str> {
             }
         } else {
             match token {
-                Variable(name) => {
+                Variable(_) => {
                     prev_token_was_arg = true;
                     continue;
                 }
@@ -0,0 +1,227 @@
+use mathengine::core::{Calc, CalcEngine, OutputLine};
+use mathengine::real::NumberMode;
+use mathengine::token::Token::{self, *};
+use mathengine::uncertainty::UncertaintyMode;
+
+fn eval(engine: &mut CalcEngine, input: &str) -> Result<Token, &'static str> {
+    engine.eval(input.as_bytes().to_vec())
+}
+
+fn number(engine: &mut CalcEngine, input: &str) -> f64 {
+    match eval(engine, input) {
+        Ok(Number(n)) => n,
+        other => panic!("{} gave {:?}", input, other),
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9 * b.abs().max(1.0)
+}
+
+#[test]
+fn arithmetic() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(number(&mut engine, "1+2*3"), 7.0);
+    assert_eq!(number(&mut engine, "(1+2)*3"), 9.0);
+    assert_eq!(number(&mut engine, "-2^2"), -4.0);
+    assert_eq!(number(&mut engine, "2^-1"), 0.5);
+    assert_eq!(number(&mut engine, "5!"), 120.0);
+    assert_eq!(number(&mut engine, "200+10%"), 220.0);
+    assert_eq!(number(&mut engine, "10%-3"), 1.0);
+    assert_eq!(number(&mut engine, "10%3"), 1.0);
+    assert_eq!(number(&mut engine, "50%*2"), 1.0);
+    assert_eq!(number(&mut engine, "6.02E23"), 6.02e23);
+    assert_eq!(number(&mut engine, "2<3"), 1.0);
+    assert_eq!(number(&mut engine, "2==3"), 0.0);
+    // spaces seperate tokens, so they can't be inside a number
+    assert_eq!(number(&mut engine, " 1 +  2 "), 3.0);
+    assert_eq!(eval(&mut engine, "1 2"), Err("invalid token sequence"));
+}
+
+#[test]
+fn variables_and_functions() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(eval(&mut engine, "x=4"), Err("assigned value to variable"));
+    assert_eq!(number(&mut engine, "x*2"), 8.0);
+    assert_eq!(eval(&mut engine, "hypot(a,b)=sqrt(a*a+b*b)"), Err("created function"));
+    assert_eq!(number(&mut engine, "hypot(3,x)"), 5.0);
+    // f() has no arguments, it isn't f(0)
+    assert_eq!(eval(&mut engine, "hypot()"), Err("incorrect argument count"));
+    assert_eq!(eval(&mut engine, "c()=5"), Err("created function"));
+    assert_eq!(number(&mut engine, "c()+1"), 6.0);
+    assert_eq!(eval(&mut engine, "c(1)"), Err("incorrect argument count"));
+    assert_eq!(eval(&mut engine, "sin(x)=x"), Err("built in functions cannot be reassigned"));
+    assert_eq!(eval(&mut engine, "y+1"), Err("Variable does not exist"));
+}
+
+#[test]
+fn formulas_and_late_binding() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "w=2").ok();
+    eval(&mut engine, "h=3").ok();
+    assert_eq!(eval(&mut engine, "area:=w*h"), Err("assigned formula to variable"));
+    eval(&mut engine, "w=5").ok();
+    assert_eq!(number(&mut engine, "area"), 15.0);
+
+    eval(&mut engine, "a=2").ok();
+    eval(&mut engine, "f(x):=a*x").ok();
+    eval(&mut engine, "a=10").ok();
+    assert_eq!(number(&mut engine, "f(3)"), 30.0);
+}
+
+#[test]
+fn statements_and_answers() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(number(&mut engine, "let a=3; let b=4; sqrt(a^2+b^2)"), 5.0);
+    assert_eq!(eval(&mut engine, "a"), Err("Variable does not exist"));
+    assert_eq!(number(&mut engine, "ans+1"), 6.0);
+    assert_eq!(number(&mut engine, "ans2"), 5.0);
+    assert_eq!(eval(&mut engine, "ans=1"), Err("ans cannot be reassigned"));
+    // lines with nothing to work out say so instead of giving a blank error
+    for input in ["", "+", ";", " ; "] {
+        assert_eq!(eval(&mut engine, input), Err("missing expression"));
+    }
+}
+
+#[test]
+fn builtins() {
+    let mut engine = CalcEngine::new();
+    assert!(close(number(&mut engine, "sin(pi/2)"), 1.0));
+    assert_eq!(eval(&mut engine, "sqrt(-1)"), Err("argument must be at least 0"));
+    assert_eq!(eval(&mut engine, "asin(2)"), Err("argument must be from -1 to 1"));
+    assert_eq!(eval(&mut engine, "sin(1,2)"), Err("incorrect argument count"));
+    engine.use_radians = false;
+    assert!(close(number(&mut engine, "sin(90)"), 1.0));
+    assert!(close(number(&mut engine, "asin(0.5)"), 30.0));
+}
+
+#[test]
+fn logs() {
+    // log is base 10 and ln is the natural log, before they were the natural log and base 2
+    let mut engine = CalcEngine::new();
+    for mode in [NumberMode::Precise, NumberMode::Fast] {
+        engine.number_mode = mode;
+        assert!((number(&mut engine, "log(1000)") - 3.0).abs() < 1e-5);
+        assert!((number(&mut engine, "ln(e^2)") - 2.0).abs() < 1e-5);
+        assert!((number(&mut engine, "ln(8)") - 8f64.ln()).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn sums_and_sequences() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(number(&mut engine, "sum(i,1,10,i^2)"), 385.0);
+    assert_eq!(number(&mut engine, "prod(k,1,5,k)"), 120.0);
+    assert_eq!(eval(&mut engine, "seq(i,0,5,2^i)"), Ok(List(Vec::from([1.0, 2.0, 4.0, 8.0, 16.0, 32.0]))));
+    assert_eq!(eval(&mut engine, "i"), Err("Variable does not exist"));
+}
+
+#[test]
+fn polynomial_roots() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(eval(&mut engine, "polyroots(1,0,-1)"), Ok(List(Vec::from([-1.0, 1.0]))));
+    assert_eq!(engine.output.len(), 2);
+
+    // big roots are found too, since the guesses start out as big as the roots can be
+    engine.output.clear();
+    match eval(&mut engine, "polyroots(1,-1000,0,0,1)") {
+        Ok(List(roots)) => assert!(close(roots[1], 1000.0), "{:?}", roots),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(engine.output.len(), 4);
+    // a repeated root never quite settles, so it says the roots are only close
+    engine.output.clear();
+    eval(&mut engine, "polyroots(1,-4,6,-4,1)").ok();
+    assert!(matches!(engine.output.last(), Some(OutputLine::Text(text)) if text == b"roots are approximate"));
+}
+
+#[test]
+fn uncertainty() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(eval(&mut engine, "(3.2+/-0.1)*2"), Ok(Uncertain(6.4, 0.2)));
+    assert_eq!(eval(&mut engine, "50+/-10%"), Ok(Uncertain(50.0, 5.0)));
+    // an added percent is a percent of the +/- value too
+    for (input, expected) in [("5+/-1 + 10%", (5.5, 1.1)), ("5+/-1 - 10%", (4.5, 0.9)), ("5 + (10+/-1)%", (5.5, 0.05))] {
+        match eval(&mut engine, input) {
+            Ok(Uncertain(value, uncertainty)) => assert!(close(value, expected.0) && close(uncertainty, expected.1), "{} gave {}+/-{}", input, value, uncertainty),
+            other => panic!("{} gave {:?}", input, other),
+        }
+    }
+    engine.uncertainty_mode = UncertaintyMode::Interval;
+    match eval(&mut engine, "(1+/-1)^2") {
+        Ok(Uncertain(value, uncertainty)) => assert!(close(value, 2.0) && close(uncertainty, 2.0)),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn fast_mode() {
+    let mut engine = CalcEngine::new();
+    engine.number_mode = NumberMode::Fast;
+    assert_eq!(number(&mut engine, "1.5*4"), 6.0);
+    assert!((number(&mut engine, "sqrt(2)") - 2f64.sqrt()).abs() < 1e-5);
+    // big numbers need more than 64 bits while the root is found
+    assert_eq!(number(&mut engine, "sqrt(1E8)"), 10000.0);
+    assert_eq!(number(&mut engine, "sqrt(1E12)"), 1000000.0);
+    eval(&mut engine, "f(x)=sqrt(x)").ok();
+    assert_eq!(number(&mut engine, "f(4E12)"), 2000000.0);
+    assert_eq!(eval(&mut engine, "f()"), Err("incorrect argument count"));
+}
+
+#[test]
+fn fast_mode_matches_precise() {
+    // everything here runs in fixed point the whole way, functions like sin included
+    let inputs = [
+        "sin(pi/6)*2",
+        "cos(1)+tan(0.5)",
+        "atan(2)*asin(0.5)-acos(0.3)",
+        "log(10)+ln(8)",
+        "2^0.5+10^-1.5",
+        "g(2)*3",
+        "sum(i,1,10,sqrt(i))",
+        "sin(1000)",
+    ];
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "g(x)=x^1.5+sin(x)").ok();
+    for input in inputs {
+        engine.number_mode = NumberMode::Precise;
+        let precise = number(&mut engine, input);
+        engine.number_mode = NumberMode::Fast;
+        let fast = number(&mut engine, input);
+        assert!((fast - precise).abs() < 1e-4 * precise.abs().max(1.0), "{} gave {} not {}", input, fast, precise);
+    }
+    assert!(number(&mut engine, "(-8)^0.5").is_nan());
+    assert_eq!(eval(&mut engine, "log(0)"), Err("argument must be more than 0"));
+}
+
+#[test]
+fn random_with_seed() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "seed(7)").ok();
+    let first = number(&mut engine, "rand()");
+    eval(&mut engine, "seed(7)").ok();
+    assert_eq!(number(&mut engine, "rand()"), first);
+    let roll = number(&mut engine, "randint(1,6)");
+    assert!((1.0..=6.0).contains(&roll) && roll.fract() == 0.0);
+}
+
+#[test]
+fn budget_and_limits() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "f(x)=f(x-1)+1").ok();
+    assert_eq!(eval(&mut engine, "f(3)"), Err("functions call each other too deeply"));
+    assert_eq!(eval(&mut engine, "sum(i,1,9999,sum(j,1,9999,i*j))"), Err("ran out of steps"));
+
+    let mut calls = 0;
+    engine.budget.poll = Some(Box::new(move || {
+        calls += 1;
+        calls < 3
+    }));
+    assert_eq!(eval(&mut engine, "sum(i,1,5000,i)"), Err("cancelled"));
+    engine.budget.poll = None;
+    assert_eq!(number(&mut engine, "sum(i,1,5000,i)"), 12502500.0);
+
+    assert_eq!(eval(&mut engine, "seq(i,1,5000,i)"), Err("list is too long"));
+    let long = "1+".repeat(300) + "1";
+    assert_eq!(eval(&mut engine, &format!("g(x)={}", long)), Err("function is too long"));
+}
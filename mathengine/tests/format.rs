@@ -0,0 +1,28 @@
+use mathengine::format::{format_complex, format_number, format_uncertain, DisplayMode};
+
+fn text(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn display_modes() {
+    assert_eq!(text(format_number(0.1 + 0.2, DisplayMode::Float, 29)), "0.3");
+    assert_eq!(text(format_number(2.5, DisplayMode::Fix(2), 29)), "2.50");
+    assert_eq!(text(format_number(12345.678, DisplayMode::Sci(3), 29)), "1.235E4");
+    assert_eq!(text(format_number(12345.678, DisplayMode::Eng(1), 29)), "12.3k");
+}
+
+#[test]
+fn fits_in_width() {
+    for n in [1.0 / 3.0, 6.02e23, -4.7e-6, 123456789.0, 2e300] {
+        for mode in [DisplayMode::Float, DisplayMode::Fix(9), DisplayMode::Sci(5), DisplayMode::Eng(5)] {
+            assert!(format_number(n, mode, 12).len() <= 12, "{} in {:?}", n, mode);
+        }
+    }
+}
+
+#[test]
+fn complex_and_uncertain() {
+    assert_eq!(text(format_complex(1.0, -2.0, DisplayMode::Float, 29)), "1-2i");
+    assert_eq!(text(format_uncertain(6.4, 0.2, DisplayMode::Float, 29)), "6.4+/-0.2");
+}
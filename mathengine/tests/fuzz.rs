@@ -0,0 +1,86 @@
+// evaluation has to give an error instead of panicking for every input, since a panic freezes the gba
+// random lines are built from pieces of the calculator's syntax, so most of them get past the tokenizer
+
+use std::panic::{self, AssertUnwindSafe};
+
+use mathengine::core::{Calc, CalcEngine};
+use mathengine::real::NumberMode;
+use mathengine::validate::validate_token_list;
+
+// raise this to fuzz for longer, eg. with cargo test --release --test fuzz
+const ITERATIONS: usize = 200000;
+
+const PIECES: [&str; 60] = [
+    "0", "1", "2", "0.5", "1E3", "1E-", ".", "x", "y", "f", "g", "ans", "ans3", "pi", "let ", " ", "+", "-", "*",
+    "/", "^", "%", "!", "(", ")", "{", "}", ",", ";", "=", ":=", "<", ">", "<=", "==", "!=", "+/-", "sin", "sqrt",
+    "asin", "sum", "seq", "ode", "ode45", "rand", "randint", "randsample", "polyroots", "tvmpmt", "npv", "irr",
+    "amort", "i", "f(x)", "g(a,b)", "seed", "prod", "E", "del ", "rename ",
+];
+
+// a small xorshift generator, so failures can be reproduced from the seed
+struct Generator(u64);
+
+impl Generator {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn line(&mut self) -> Vec<u8> {
+        let mut line = Vec::new();
+        for _ in 0..self.below(12) + 1 {
+            // mostly pieces, with the odd random byte
+            if self.below(16) == 0 {
+                line.push(self.next() as u8);
+            } else {
+                line.extend_from_slice(PIECES[self.below(PIECES.len())].as_bytes());
+            }
+        }
+        line
+    }
+}
+
+fn check(engine: &mut CalcEngine, input: &[u8]) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Ok(tokens) = engine.tokenize(input) {
+            validate_token_list(&tokens);
+        }
+        let _ = engine.eval(input.to_vec());
+    }));
+    if result.is_err() {
+        panic!("panicked on {:?}", String::from_utf8_lossy(input));
+    }
+}
+
+#[test]
+fn known_inputs() {
+    let mut engine = CalcEngine::new();
+    let inputs: [&[u8]; 24] = [
+        b"", b"-", b"+", b"(", b")", b"f()", b"x=", b"=", b"=1", b"()=1", b"f(=1", b"let", b"let =", b";;", b",",
+        b"sin", b"sin()", b"sum(i,1,2)", b"1E", b"..", b"\xff", b"f(x)=", b"()", b"()!",
+    ];
+    for input in inputs {
+        check(&mut engine, input);
+    }
+}
+
+#[test]
+fn random_inputs() {
+    let mut generator = Generator(0x9e3779b97f4a7c15);
+    let mut engine = CalcEngine::new();
+    // some things for the lines to use, and to be redefined by them
+    let _ = engine.eval(b"x=2".to_vec());
+    let _ = engine.eval(b"f(x)=x^2".to_vec());
+    for _ in 0..ITERATIONS {
+        // FAST mode has its own fixed point math, so it gets half the lines
+        engine.number_mode = if generator.below(2) == 0 { NumberMode::Fast } else { NumberMode::Precise };
+        let line = generator.line();
+        check(&mut engine, &line);
+    }
+}
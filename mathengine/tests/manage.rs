@@ -0,0 +1,168 @@
+use mathengine::core::{Calc, CalcEngine, OutputLine};
+use mathengine::manage::Saved;
+use mathengine::program::{Program, ProgramHandling, RunStatus};
+use mathengine::token::Token::*;
+
+fn eval(engine: &mut CalcEngine, input: &str) -> Result<mathengine::token::Token, &'static str> {
+    engine.eval(input.as_bytes().to_vec())
+}
+
+fn listed(engine: &mut CalcEngine) -> Vec<String> {
+    engine.output.clear();
+    eval(engine, "list").ok();
+    engine
+        .output
+        .drain(..)
+        .map(|line| match line {
+            OutputLine::Text(text) => String::from_utf8(text).unwrap(),
+            _ => panic!("list only shows text"),
+        })
+        .collect()
+}
+
+#[test]
+fn list_shows_definitions() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "x=4").ok();
+    eval(&mut engine, "area:=x*2").ok();
+    eval(&mut engine, "f(a)=a+1").ok();
+    assert_eq!(eval(&mut engine, "list"), Err("listed variables and functions"));
+    assert_eq!(listed(&mut engine)[..], ["area:=x*2", "e=2.718281828459045", "pi=3.141592653589793", "tau=6.283185307179586", "x=4", "f(a)=a+1"]);
+}
+
+#[test]
+fn delete_and_clear() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "x=4").ok();
+    eval(&mut engine, "f(a)=a+1").ok();
+    assert_eq!(eval(&mut engine, "del x"), Err("deleted"));
+    assert_eq!(eval(&mut engine, "x"), Err("Variable does not exist"));
+    assert_eq!(eval(&mut engine, "del x"), Err("nothing has that name"));
+    assert_eq!(eval(&mut engine, "del nope"), Err("nothing has that name"));
+    assert_eq!(eval(&mut engine, "del"), Err("del needs a name, eg. del x"));
+
+    eval(&mut engine, "y=2").ok();
+    eval(&mut engine, "pi=3").ok();
+    assert_eq!(eval(&mut engine, "clear vars"), Err("cleared variables"));
+    assert_eq!(eval(&mut engine, "y"), Err("Variable does not exist"));
+    assert_eq!(eval(&mut engine, "pi*0+f(1)"), Ok(Number(2.0)));
+    assert_eq!(eval(&mut engine, "clear funcs"), Err("cleared functions"));
+    assert!(engine.saved().iter().all(|saved| matches!(saved, Saved::Variable(_))));
+
+    eval(&mut engine, "z=1").ok();
+    eval(&mut engine, "5").ok();
+    assert_eq!(eval(&mut engine, "reset"), Err("cleared everything"));
+    assert_eq!(listed(&mut engine).len(), 3);
+    assert_eq!(eval(&mut engine, "ans"), Ok(Number(0.0)));
+}
+
+#[test]
+fn delete_recomputes_formulas() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "w=2").ok();
+    eval(&mut engine, "h=3").ok();
+    eval(&mut engine, "area:=w*h").ok();
+    eval(&mut engine, "double:=area*2").ok();
+    assert_eq!(eval(&mut engine, "area"), Ok(Number(6.0)));
+    assert_eq!(eval(&mut engine, "del w"), Err("deleted"));
+    // the formulas are kept, but have no value until w is back
+    assert_eq!(eval(&mut engine, "area"), Err("Variable does not exist"));
+    assert_eq!(eval(&mut engine, "double"), Err("Variable does not exist"));
+    assert_eq!(listed(&mut engine).iter().filter(|line| line.contains(":=")).count(), 2);
+    eval(&mut engine, "w=5").ok();
+    assert_eq!(eval(&mut engine, "double"), Ok(Number(30.0)));
+    assert_eq!(eval(&mut engine, "del area"), Err("deleted"));
+    assert_eq!(eval(&mut engine, "double"), Err("Variable does not exist"));
+}
+
+#[test]
+fn rename() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "x=4").ok();
+    eval(&mut engine, "f(a)=a+1").ok();
+    assert_eq!(eval(&mut engine, "rename x width"), Err("renamed"));
+    assert_eq!(eval(&mut engine, "width"), Ok(Number(4.0)));
+    assert_eq!(eval(&mut engine, "x"), Err("Variable does not exist"));
+    assert_eq!(eval(&mut engine, "rename f g"), Err("renamed"));
+    assert_eq!(eval(&mut engine, "g(1)"), Ok(Number(2.0)));
+    assert_eq!(eval(&mut engine, "rename g sin"), Err("built in functions cannot be reassigned"));
+    assert_eq!(eval(&mut engine, "rename g width"), Err("that name is already used"));
+    assert_eq!(eval(&mut engine, "rename g 2x"), Err("that isn't a name"));
+    assert_eq!(eval(&mut engine, "rename g"), Err("rename needs 2 names, eg. rename x y"));
+}
+
+#[test]
+fn command_words_are_not_names() {
+    let mut engine = CalcEngine::new();
+    assert_eq!(eval(&mut engine, "list=3"), Err("that name is reserved"));
+    assert_eq!(eval(&mut engine, "reset(a)=a"), Err("that name is reserved"));
+    assert_eq!(eval(&mut engine, "mem+1"), Err("that name is reserved"));
+    assert_eq!(eval(&mut engine, "for=1"), Err("that name is reserved"));
+    eval(&mut engine, "x=1").ok();
+    assert_eq!(eval(&mut engine, "rename x clear"), Err("that isn't a name"));
+    assert_eq!(eval(&mut engine, "x"), Ok(Number(1.0)));
+}
+
+#[test]
+fn rename_updates_formulas_and_functions() {
+    let mut engine = CalcEngine::new();
+    eval(&mut engine, "x=2").ok();
+    eval(&mut engine, "y:=x*3").ok();
+    eval(&mut engine, "f(a):=a+x").ok();
+    eval(&mut engine, "g(a)=f(a)*2").ok();
+    assert_eq!(eval(&mut engine, "rename x z"), Err("renamed"));
+    eval(&mut engine, "z=5").ok();
+    assert_eq!(eval(&mut engine, "y"), Ok(Number(15.0)));
+    assert_eq!(eval(&mut engine, "f(1)"), Ok(Number(6.0)));
+    // functions that call a renamed function call it by its new name
+    assert_eq!(eval(&mut engine, "rename f h"), Err("renamed"));
+    assert_eq!(eval(&mut engine, "g(1)"), Ok(Number(12.0)));
+}
+
+#[test]
+fn memory_frees_names() {
+    let mut engine = CalcEngine::new();
+    let names = engine.memory_usage().names;
+    eval(&mut engine, "longname=4").ok();
+    eval(&mut engine, "typo+1").ok();
+    eval(&mut engine, "del longname").ok();
+    // names are freed before the next line, so typos and deleted names don't use memory forever
+    eval(&mut engine, "1").ok();
+    assert_eq!(engine.memory_usage().names, names);
+    eval(&mut engine, "y=2").ok();
+    assert_eq!(eval(&mut engine, "y*longname"), Err("Variable does not exist"));
+    assert_eq!(eval(&mut engine, "y"), Ok(Number(2.0)));
+
+    engine.output.clear();
+    assert_eq!(eval(&mut engine, "mem"), Err("bytes are an estimate"));
+    assert_eq!(engine.output.len(), 5);
+}
+
+#[test]
+fn memory_total_is_kept_up_to_date() {
+    let mut engine = CalcEngine::new();
+    let lines = [
+        "a=1", "b=[1,2,3]", "a=b", "c:=a*2", "c", "f(x)=x+1", "f(2)", "b=5", "c:=1", "for i=1 to 20; d=i; end",
+    ];
+    for line in lines {
+        eval(&mut engine, line).ok();
+        // the total kept while the line ran is the same as counting everything again
+        let bytes = engine.memory_usage().bytes;
+        engine.recount_memory();
+        assert_eq!(engine.memory_usage().bytes, bytes, "after {}", line);
+    }
+}
+
+#[test]
+fn prompt_keeps_names() {
+    let mut engine = CalcEngine::new();
+    let lines = ["prompt side", "disp side*2"].iter().map(|line| line.as_bytes().to_vec()).collect();
+    engine.programs.insert(b"p".to_vec(), Program { lines });
+    assert!(matches!(engine.run_program(b"p"), Ok(RunStatus::Prompt(_))));
+    // the program still needs side, even though no variable has that name yet
+    eval(&mut engine, "other=1").ok();
+    assert!(matches!(engine.answer_prompt(b"3"), Ok(RunStatus::Finished)));
+    assert!(matches!(engine.output.pop(), Some(OutputLine::Value(_, Number(n))) if n == 6.0));
+    assert_eq!(eval(&mut engine, "side"), Ok(Number(3.0)));
+    assert_eq!(eval(&mut engine, "other"), Ok(Number(1.0)));
+}
@@ -0,0 +1,101 @@
+use mathengine::core::{Calc, CalcEngine};
+use mathengine::manage::Saved;
+use mathengine::print::to_text;
+use mathengine::token::Token::*;
+
+fn text(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).unwrap()
+}
+
+fn define(engine: &mut CalcEngine, input: &str) {
+    assert!(engine.eval(input.as_bytes().to_vec()).is_err(), "{} should be a definition", input);
+}
+
+#[test]
+fn functions_keep_their_argument_names() {
+    let mut engine = CalcEngine::new();
+    define(&mut engine, "hypot(a,b)=sqrt(a*a+b*b)");
+    define(&mut engine, "k=3");
+    define(&mut engine, "f(x):=k*x^2");
+    define(&mut engine, "g(x)=k*x!");
+    let (hypot, f, g) = (engine.symbols.intern(b"hypot"), engine.symbols.intern(b"f"), engine.symbols.intern(b"g"));
+    assert_eq!(text(engine.function_text(hypot).unwrap()), "hypot(a,b)=sqrt(a*a+b*b)");
+    // ':=' keeps its variables, '=' has their values put in
+    assert_eq!(text(engine.function_text(f).unwrap()), "f(x):=k*x^2");
+    assert_eq!(text(engine.function_text(g).unwrap()), "g(x)=3*x!");
+    let missing = engine.symbols.intern(b"missing");
+    assert_eq!(engine.function_text(missing), None);
+}
+
+#[test]
+fn variables_and_formulas() {
+    let mut engine = CalcEngine::new();
+    define(&mut engine, "w=2.5");
+    define(&mut engine, "h=6.02E23");
+    define(&mut engine, "area:=w*h");
+    let (w, h, area) = (engine.symbols.intern(b"w"), engine.symbols.intern(b"h"), engine.symbols.intern(b"area"));
+    assert_eq!(text(engine.variable_text(w).unwrap()), "w=2.5");
+    assert_eq!(text(engine.variable_text(h).unwrap()), "h=6.02E23");
+    assert_eq!(text(engine.variable_text(area).unwrap()), "area:=w*h");
+}
+
+#[test]
+fn negative_numbers_keep_their_meaning() {
+    let symbols = CalcEngine::new().symbols;
+    assert_eq!(text(to_text(&[Number(-3.0), Exponentation, Number(2.0)], &symbols, &[]).unwrap()), "(-3)^2");
+    assert_eq!(text(to_text(&[Number(2.0), Exponentation, Number(-3.0)], &symbols, &[]).unwrap()), "2^-3");
+    assert_eq!(text(to_text(&[Uncertain(3.2, 0.1), Multiplication, Number(2.0)], &symbols, &[]).unwrap()), "(3.2+/-0.1)*2");
+    assert_eq!(text(to_text(&[Number(1e-9)], &symbols, &[]).unwrap()), "1E-9");
+    assert_eq!(text(to_text(&[Number(f64::NEG_INFINITY), Exponentation, Number(2.0)], &symbols, &[]).unwrap()), "(-1/0)^2");
+    assert_eq!(text(to_text(&[Number(f64::NAN)], &symbols, &[]).unwrap()), "(0/0)");
+    assert_eq!(to_text(&[Number(1.0), Addition, List(Vec::from([1.0, 2.0]))], &symbols, &[]), None);
+}
+
+#[test]
+fn printed_definitions_give_the_same_answers() {
+    let definitions = ["p(x)=-2*x^2+x/3-1", "q(a,b)=(a-b)^-2", "r(t)=sum(i,1,t,i*t)", "s(x)=-1.5^x"];
+    let mut engine = CalcEngine::new();
+    let mut printed = Vec::new();
+    for definition in definitions {
+        define(&mut engine, definition);
+        let name = engine.symbols.intern(&definition.as_bytes()[..1]);
+        printed.push(engine.function_text(name).unwrap());
+    }
+
+    let mut copy = CalcEngine::new();
+    for text in printed {
+        assert!(copy.eval(text).is_err());
+    }
+    for call in ["p(1.7)", "q(4,1.5)", "r(6)", "s(3)"] {
+        assert_eq!(engine.eval(call.as_bytes().to_vec()), copy.eval(call.as_bytes().to_vec()), "{}", call);
+    }
+}
+
+#[test]
+fn infinity_and_lists_are_never_printed_as_something_else() {
+    let mut engine = CalcEngine::new();
+    define(&mut engine, "big=1E308*10");
+    define(&mut engine, "low=-big");
+    define(&mut engine, "t(x)=2^x-big");
+    let (big, low, t) = (engine.symbols.intern(b"big"), engine.symbols.intern(b"low"), engine.symbols.intern(b"t"));
+    let printed = [engine.variable_text(big).unwrap(), engine.variable_text(low).unwrap(), engine.function_text(t).unwrap()];
+    assert_eq!(text(printed[0].clone()), "big=(1/0)");
+    assert_eq!(text(printed[1].clone()), "low=(-1/0)");
+
+    let mut copy = CalcEngine::new();
+    for text in printed {
+        assert!(copy.eval(text).is_err());
+    }
+    for call in ["big", "low", "t(3)"] {
+        assert_eq!(engine.eval(call.as_bytes().to_vec()), copy.eval(call.as_bytes().to_vec()), "{}", call);
+    }
+
+    // a list can't be typed in, so it has no text and list just shows its name
+    define(&mut engine, "roots=polyroots(1,0,-1)");
+    define(&mut engine, "shifted(x)=x+roots");
+    let (roots, shifted) = (engine.symbols.intern(b"roots"), engine.symbols.intern(b"shifted"));
+    assert_eq!(engine.variable_text(roots), None);
+    assert_eq!(engine.function_text(shifted), None);
+    assert_eq!(text(engine.saved_text(Saved::Variable(roots))), "roots");
+    assert_eq!(text(engine.saved_text(Saved::Function(shifted))), "shifted");
+}
@@ -0,0 +1,75 @@
+use mathengine::core::{CalcEngine, OutputLine};
+use mathengine::program::{Program, ProgramHandling, RunStatus};
+use mathengine::token::Token::{self, *};
+
+fn add_program(engine: &mut CalcEngine, name: &str, lines: &[&str]) {
+    let lines = lines.iter().map(|line| line.as_bytes().to_vec()).collect();
+    engine.programs.insert(name.as_bytes().to_vec(), Program { lines });
+}
+
+fn shown(engine: &mut CalcEngine) -> Vec<Token> {
+    engine
+        .output
+        .drain(..)
+        .map(|line| match line {
+            OutputLine::Value(_, value) => value,
+            _ => panic!("programs only show values"),
+        })
+        .collect()
+}
+
+#[test]
+fn loops_and_ifs() {
+    let mut engine = CalcEngine::new();
+    add_program(&mut engine, "sum", &["s=0", "for i,1,10", "s=s+i", "end", "disp s"]);
+    add_program(&mut engine, "down", &["for(i,3,1,-1)", "disp i", "end"]);
+    add_program(&mut engine, "ifs", &["x=5", "if x>3", "disp 1", "else", "disp 2", "end"]);
+    add_program(&mut engine, "double", &["n=1", "while n<100", "n=n*2", "end", "disp n"]);
+
+    assert!(matches!(engine.run_program(b"sum"), Ok(RunStatus::Finished)));
+    assert_eq!(shown(&mut engine), [Number(55.0)]);
+    engine.run_program(b"down").ok();
+    assert_eq!(shown(&mut engine), [Number(3.0), Number(2.0), Number(1.0)]);
+    engine.run_program(b"ifs").ok();
+    assert_eq!(shown(&mut engine), [Number(1.0)]);
+    engine.run_program(b"double").ok();
+    assert_eq!(shown(&mut engine), [Number(128.0)]);
+
+    // long loops only stop when they run out of steps
+    add_program(&mut engine, "long", &["s=0", "for i,1,10000", "s=s+i", "end", "disp s"]);
+    assert!(matches!(engine.run_program(b"long"), Ok(RunStatus::Finished)));
+    assert_eq!(shown(&mut engine), [Number(50005000.0)]);
+}
+
+#[test]
+fn names_that_start_with_keywords() {
+    let mut engine = CalcEngine::new();
+    add_program(&mut engine, "names", &["end_x=3", "if1=2", "disp2=end_x+if1", "disp disp2"]);
+    add_program(&mut engine, "keyword", &["end=1"]);
+    assert!(matches!(engine.run_program(b"names"), Ok(RunStatus::Finished)));
+    assert_eq!(shown(&mut engine), [Number(5.0)]);
+    assert_eq!(engine.run_program(b"keyword").err(), Some("end without a block"));
+}
+
+#[test]
+fn prompts() {
+    let mut engine = CalcEngine::new();
+    add_program(&mut engine, "area", &["prompt r", "disp r^2"]);
+    match engine.run_program(b"area") {
+        Ok(RunStatus::Prompt(name)) => assert_eq!(name, b"r"),
+        _ => panic!("should prompt for r"),
+    }
+    assert!(matches!(engine.answer_prompt(b"3"), Ok(RunStatus::Finished)));
+    assert_eq!(shown(&mut engine), [Number(9.0)]);
+    assert!(engine.answer_prompt(b"3").is_err());
+}
+
+#[test]
+fn bad_programs() {
+    let mut engine = CalcEngine::new();
+    add_program(&mut engine, "forever", &["while 1", "end"]);
+    add_program(&mut engine, "unclosed", &["if 1", "disp 1"]);
+    assert_eq!(engine.run_program(b"forever").err(), Some("ran out of steps"));
+    assert!(engine.run_program(b"unclosed").is_err());
+    assert!(engine.run_program(b"missing").is_err());
+}
@@ -0,0 +1,96 @@
+use mathengine::core::{Calc, CalcEngine, OutputLine};
+use mathengine::finance::FinanceHandling;
+use mathengine::linear::LinearHandling;
+use mathengine::memory::MAX_TABLE_ROWS;
+use mathengine::token::Token::*;
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() < tolerance
+}
+
+#[test]
+fn linear_systems() {
+    let mut engine = CalcEngine::new();
+    // 2x + y = 5, x - y = 1
+    assert_eq!(engine.solve_system(2, &[2.0, 1.0, 5.0, 1.0, -1.0, 1.0]), Ok(()));
+    assert_eq!(engine.output.len(), 2);
+    engine.output.clear();
+    assert_eq!(engine.eval(b"x1*10+x2".to_vec()), Ok(Number(21.0)));
+
+    assert!(engine.solve_system(2, &[1.0, 2.0, 3.0, 2.0, 4.0, 6.0]).is_err());
+    assert!(engine.solve_system(2, &[1.0, 2.0, 3.0, 2.0, 4.0, 7.0]).is_err());
+    assert!(engine.solve_system(2, &[1.0]).is_err());
+}
+
+#[test]
+fn time_value_of_money() {
+    let mut engine = CalcEngine::new();
+    // the monthly payment on a 30 year loan at 6%
+    let values = [Some(360.0), Some(6.0), Some(200000.0), None, Some(0.0), Some(12.0), Some(12.0)];
+    assert_eq!(engine.solve_tvm(&values), Ok(()));
+    match engine.output.pop() {
+        Some(OutputLine::Value(_, Number(payment))) => assert!(close(payment, -1199.10105, 1e-4)),
+        _ => panic!("tvm should give the payment"),
+    }
+    // no N at 0% without payments, and no payment over 0 periods
+    let no_n = [None, Some(0.0), Some(1000.0), Some(0.0), Some(-1000.0), None, None];
+    assert_eq!(engine.solve_tvm(&no_n), Err("no answer for these values"));
+    let no_pmt = [Some(0.0), Some(6.0), Some(1000.0), None, Some(0.0), None, None];
+    assert_eq!(engine.solve_tvm(&no_pmt), Err("no answer for these values"));
+    assert!(engine.output.is_empty());
+
+    match engine.eval(b"tvmpmt(360,6,200000,0,12)".to_vec()) {
+        Ok(Number(payment)) => assert!(close(payment, -1199.10105, 1e-4)),
+        other => panic!("{:?}", other),
+    }
+    match engine.eval(b"npv(10,-100,110)".to_vec()) {
+        Ok(Number(value)) => assert!(close(value, 0.0, 1e-9)),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn differential_equations() {
+    let mut engine = CalcEngine::new();
+    engine.eval(b"f(t,y)=y".to_vec()).ok();
+    match engine.eval(b"ode(f,0,1,1,100)".to_vec()) {
+        Ok(Number(y)) => assert!(close(y, core::f64::consts::E, 1e-6)),
+        other => panic!("{:?}", other),
+    }
+    let table = engine.table.take().unwrap();
+    assert_eq!(table.rows().len(), 101);
+
+    // a long one only keeps some of the rows, but still ends at t1
+    match engine.eval(b"ode(f,0,1,1,10000)".to_vec()) {
+        Ok(Number(y)) => assert!(close(y, core::f64::consts::E, 1e-9)),
+        other => panic!("{:?}", other),
+    }
+    let table = engine.table.take().unwrap();
+    assert!(table.rows().len() <= MAX_TABLE_ROWS + 1);
+    assert_eq!(table.rows().next(), Some(&[0.0, 1.0][..]));
+    let last = table.rows().last().unwrap();
+    assert!(close(last[0], 1.0, 1e-9) && close(last[1], core::f64::consts::E, 1e-9));
+
+    match engine.eval(b"ode45(f,0,1,1)".to_vec()) {
+        Ok(Number(y)) => assert!(close(y, core::f64::consts::E, 1e-4)),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn amortization() {
+    let mut engine = CalcEngine::new();
+    engine.eval(b"p=tvmpmt(360,6,200000,0,12)".to_vec()).ok();
+    match engine.eval(b"amort(360,6,200000,p,12)".to_vec()) {
+        Ok(Number(balance)) => assert!(close(balance, 0.0, 1e-6)),
+        other => panic!("{:?}", other),
+    }
+    let table = engine.table.take().unwrap();
+    assert_eq!(table.rows().len(), 360);
+
+    // 10000 periods only keep some rows, the last one is still the last period
+    assert!(engine.eval(b"amort(10000,1,1000,-1)".to_vec()).is_ok());
+    let table = engine.table.take().unwrap();
+    assert!(table.rows().len() <= MAX_TABLE_ROWS + 1);
+    assert_eq!(table.rows().last().unwrap()[0], 10000.0);
+}
@@ -7,7 +7,6 @@
 extern crate alloc;
 
 mod display;
-mod mathengine;
 mod ui;
 
 use agb::{
@@ -33,15 +32,29 @@ use agb::{
 };
 use alloc::*;
 use alloc::{
+    boxed::Box,
+    rc::Rc,
     string::{String, ToString},
     vec::{self, Vec},
 };
-use mathengine::core::{Calc, CalcEngine};
+use core::cell::RefCell;
+use mathengine::core::{Calc, CalcEngine, OutputLine, ANSWER_COUNT};
+use mathengine::token::Token;
+use mathengine::format::{format_complex, format_number, format_uncertain, DisplayMode};
+use mathengine::real::NumberMode;
+use mathengine::uncertainty::UncertaintyMode;
+use mathengine::finance::FinanceHandling;
+use mathengine::linear::LinearHandling;
+use mathengine::manage::Saved;
+use mathengine::program::{Program, ProgramHandling, RunStatus};
 use ui::core::Interface;
 
 use crate::{
     display::display::Drawing,
-    ui::{calculator::calc_ui, core::UI},
+    ui::{
+        calculator::{calc_ui, form::{Form, FormKind}, table::Table},
+        core::UI,
+    },
 };
 
 pub type Cursor<'a> = (Object<'a>, Object<'a>, Object<'a>, Object<'a>);
@@ -51,9 +64,16 @@ pub fn run(mut gba: agb::Gba) -> ! {
     // initialize gameboy
     let (gfx, mut vram) = gba.display.video.tiled0();
     let vblank = agb::interrupt::VBlank::get();
-    let mut input = ButtonController::new();
-    let mut mixer = gba.mixer.mixer(Frequency::Hz10512);
-    mixer.enable();
+    // the engine's poll uses the buttons and the mixer during long calculations, so they are shared with the main loop
+    // with one controller, the B press that cancels has already been seen by the time the main loop checks, so it isn't a backspace too
+    let input = Rc::new(RefCell::new(ButtonController::new()));
+    let mixer = Rc::new(RefCell::new(gba.mixer.mixer(Frequency::Hz10512)));
+    mixer.borrow_mut().enable();
+
+    // a fast timer, when the buttons get pressed is random enough to seed rand with
+    let mut timers = gba.timers.timers();
+    let entropy_timer: &mut Timer = &mut timers.timer2;
+    entropy_timer.set_divider(timer::Divider::Divider1).set_enabled(true);
 
     // initialize background tileset
     agb::include_background_gfx!(text_tiles, tiles => "gfx/tile_sheet.png");
@@ -100,11 +120,31 @@ pub fn run(mut gba: agb::Gba) -> ! {
 
     let mut calculator = Calculator {
         interface: calc_ui::make_ui(),
+        overlay: None,
         history: core::array::from_fn(|_| b"".to_vec()),
         user_input: b"".to_vec(),
         engine: CalcEngine::new(),
+        display_mode: DisplayMode::Float,
+        answer_cycle: None,
+        editing: None,
+        form: None,
+        table: None,
+        catalog: None,
+        programs: None,
+        memory: None,
+        memory_selected: None,
     };
 
+    // long evaluations call this every so often, so the sound keeps going and holding B cancels them
+    let poll_mixer = mixer.clone();
+    let poll_input = input.clone();
+    calculator.engine.budget.poll = Some(Box::new(move || {
+        poll_mixer.borrow_mut().frame();
+        let mut input = poll_input.borrow_mut();
+        input.update();
+        !input.is_pressed(Button::B)
+    }));
+
     let mut update_screen: bool = { true };
 
     {
@@ -112,7 +152,7 @@ pub fn run(mut gba: agb::Gba) -> ! {
         vblank.wait_for_vblank();
 
         // draw all tiles to  foregrond and background
-        calculator.interface.draw_interface(
+        calculator.active_interface().draw_interface(
             UI_POSITION,
             &mut bg,
             &mut fg,
@@ -131,13 +171,20 @@ pub fn run(mut gba: agb::Gba) -> ! {
 
     loop {
         // update and handle UI input
-        input.update();
-        let (user_pressed_button, command) = calculator.interface.handle_input(&mut input);
+        input.borrow_mut().update();
+        let (user_pressed_button, command) = calculator.active_interface().handle_input(&mut input.borrow_mut());
 
         if user_pressed_button != 0 {
+            calculator.engine.rng.mix(entropy_timer.value() as u64);
             if user_pressed_button == 3 {
-                calculator.user_input.pop();
+                calculator.backspace();
             } else {
+                // anything that evaluates could take a while, so show that it's working first
+                if command == b"cmd_enter" || command == b"cmd_run_program" {
+                    vblank.wait_for_vblank();
+                    fg.print(&mut vram, &tileset, b"BUSY", &Vector2D::new(BUSY_X, SEPERATOR_Y));
+                    fg.commit(&mut vram);
+                }
                 calculator.handle_command(command);
             }
         }
@@ -156,7 +203,7 @@ pub fn run(mut gba: agb::Gba) -> ! {
             vblank.wait_for_vblank();
 
             // draw all tiles to  foregrond and background
-            calculator.interface.draw_interface(
+            calculator.active_interface().draw_interface(
                 UI_POSITION,
                 &mut bg,
                 &mut fg,
@@ -174,25 +221,361 @@ pub fn run(mut gba: agb::Gba) -> ! {
         }
 
         // do audio
-        mixer.frame();
+        mixer.borrow_mut().frame();
         if user_pressed_button == 1 {
             let mut channel = SoundChannel::new(BUTTON_PRESS);
             channel.stereo();
-            let _ = mixer.play_sound(channel);
+            let _ = mixer.borrow_mut().play_sound(channel);
         }
         if user_pressed_button == 2 || user_pressed_button == 3 {
             let mut channel = SoundChannel::new(BUTTON_RELEASE);
             channel.stereo();
-            let _ = mixer.play_sound(channel);
+            let _ = mixer.borrow_mut().play_sound(channel);
         }
     }
 }
 
 struct Calculator {
     interface: Interface,
+    // a screen like settings that replaces the keyboard while it is open
+    overlay: Option<Interface>,
     history: [Vec<u8>; 14],
     user_input: Vec<u8>,
     engine: CalcEngine,
+    display_mode: DisplayMode,
+    // which previous answer select put in last, so pressing it again can go further back
+    answer_cycle: Option<usize>,
+    // the program whose lines enter is adding to, instead of running them
+    editing: Option<Vec<u8>>,
+    // a solver screen that is being filled in, like the linear solver
+    form: Option<Form>,
+    // results from something like ode, shown until the table screen is closed
+    table: Option<Table>,
+    // the page of the built in function catalog that is open
+    catalog: Option<usize>,
+    // the page of existing programs on the program screen
+    programs: Option<usize>,
+    // the page of the memory screen that is open, and the variable or function picked on it
+    memory: Option<usize>,
+    memory_selected: Option<Saved>,
+}
+
+// the row of the bar that seperates keyboard and screen
+const SEPERATOR_Y: u16 = 14;
+// where BUSY is shown on the bar while something is being worked out
+const BUSY_X: u16 = 25;
+
+// answers get a '>' in front of them, so they have 1 less column than the screen
+const ANSWER_WIDTH: usize = 29;
+const SCREEN_WIDTH: usize = 30;
+// how much room each answer gets in the previous answer picker
+const PICKER_ANSWER_WIDTH: usize = 7;
+
+// the name the user types for a previous answer, the newest one is just ans
+fn answer_name(index: usize) -> Vec<u8> {
+    if index == 0 {
+        b"ans".to_vec()
+    } else {
+        format!("ans{}", index + 1).into_bytes()
+    }
+}
+
+impl Calculator {
+    fn active_interface(&mut self) -> &mut Interface {
+        match &mut self.overlay {
+            Some(overlay) => overlay,
+            None => &mut self.interface,
+        }
+    }
+
+    fn format_answer(&self, answer: &Token, width: usize) -> Vec<u8> {
+        match answer {
+            Token::Number(n) => format_number(*n, self.display_mode, width),
+            Token::Uncertain(value, uncertainty) => format_uncertain(*value, *uncertainty, self.display_mode, width),
+            Token::List(list) => {
+                // eg. {1,2,4}, cut off with .. if it doesn't fit
+                let mut text = b"{".to_vec();
+                for (i, n) in list.iter().enumerate() {
+                    if i > 0 {
+                        text.push(b',');
+                    }
+                    text.extend(format_number(*n, self.display_mode, width));
+                }
+                text.push(b'}');
+                if text.len() > width {
+                    text.truncate(width.saturating_sub(2));
+                    text.extend_from_slice(b"..");
+                }
+                text
+            }
+            _ => b"?".to_vec(),
+        }
+    }
+
+    fn push_history(&mut self, line: Vec<u8>) {
+        self.history.rotate_right(1);
+        self.history[0] = line;
+    }
+
+    fn backspace(&mut self) {
+        // backspacing an empty line in a form goes back a field, or closes it from the first one
+        if self.user_input.is_empty() {
+            if let Some(form) = &mut self.form {
+                if form.field == 0 {
+                    self.form = None;
+                } else {
+                    form.field -= 1;
+                }
+                return;
+            }
+        }
+        // backspacing an empty line while editing takes the last line off the program
+        if self.user_input.is_empty() {
+            if let Some(name) = &self.editing {
+                self.engine.remove_program_line(name);
+                return;
+            }
+        }
+        self.user_input.pop();
+    }
+
+    // the lines drawn instead of the history, for the program editor, forms, tables and the catalog
+    fn screen_lines(&self) -> Option<Vec<Vec<u8>>> {
+        if let Some(page) = self.catalog {
+            // how each function is typed, then what it does
+            let mut lines: Vec<Vec<u8>> = Vec::new();
+            let builtins = self.engine.builtins.all().iter().skip(page * calc_ui::CATALOG_PAGE_SIZE);
+            for builtin in builtins.take(calc_ui::CATALOG_PAGE_SIZE) {
+                lines.push(builtin.usage());
+                let mut help = b"  ".to_vec();
+                help.extend_from_slice(builtin.help.as_bytes());
+                lines.push(help);
+            }
+            return Some(lines);
+        }
+        if let Some(page) = self.memory {
+            // how each variable and function would be typed, the picked one has a '>'
+            let saved = self.engine.saved();
+            if saved.is_empty() {
+                return Some(Vec::from([b"nothing is saved".to_vec()]));
+            }
+            let mut lines: Vec<Vec<u8>> = Vec::new();
+            for entry in saved.iter().skip(page * calc_ui::MEMORY_PAGE_SIZE).take(calc_ui::MEMORY_PAGE_SIZE) {
+                let mut line = if self.memory_selected == Some(*entry) { b">".to_vec() } else { b" ".to_vec() };
+                line.extend(self.engine.saved_text(*entry));
+                line.truncate(SCREEN_WIDTH);
+                lines.push(line);
+            }
+            return Some(lines);
+        }
+        if let Some(table) = &self.table {
+            return Some(table.lines(self.history.len(), |n, width| format_number(n, self.display_mode, width)));
+        }
+        if let Some(form) = &self.form {
+            return Some(form.lines(self.history.len()));
+        }
+        self.editing
+            .as_ref()
+            .and_then(|name| self.engine.programs.get(name))
+            .map(|program| program.lines.clone())
+    }
+
+    // solves a form once all of its fields are filled in, it stays open at the bad field if something is wrong
+    fn submit_form(&mut self) {
+        let mut form = match self.form.take() {
+            Some(form) => form,
+            None => return,
+        };
+
+        // blank fields are None
+        let mut values: Vec<Option<f64>> = Vec::new();
+        for (i, text) in form.values.iter().enumerate() {
+            if text.is_empty() {
+                values.push(None);
+                continue;
+            }
+            match self.engine.tokenize(text).and_then(|tokens| self.engine.eval_expression(tokens)) {
+                Ok(Token::Number(n)) => values.push(Some(n)),
+                result => {
+                    let mut message = form.labels[i].clone();
+                    message.extend_from_slice(b": ");
+                    message.extend_from_slice(result.err().unwrap_or("must be a number").as_bytes());
+                    form.message = Some(message);
+                    form.field = i;
+                    self.form = Some(form);
+                    return;
+                }
+            }
+        }
+
+        let result = match form.kind {
+            FormKind::Linear(size) => {
+                let values: Vec<f64> = values.iter().map(|value| value.unwrap_or(0.0)).collect();
+                self.engine.solve_system(size, &values)
+            }
+            FormKind::Tvm => self.engine.solve_tvm(&values),
+        };
+        match result {
+            Ok(()) => {
+                self.push_history(form.title.clone());
+                self.show_output();
+            }
+            Err(e) => {
+                form.message = Some(e.as_bytes().to_vec());
+                form.field = 0;
+                self.form = Some(form);
+            }
+        }
+    }
+
+    // puts the extra lines from disp or polyroots in the history, and opens any table of results
+    fn show_output(&mut self) {
+        let output: Vec<OutputLine> = self.engine.output.drain(..).collect();
+        for output_line in output {
+            let mut line = b">".to_vec();
+            match output_line {
+                OutputLine::Value(label, value) => {
+                    let width = ANSWER_WIDTH.saturating_sub(label.len());
+                    line.extend(label);
+                    line.extend(self.format_answer(&value, width));
+                }
+                OutputLine::Complex(label, re, im) => {
+                    let width = ANSWER_WIDTH.saturating_sub(label.len());
+                    line.extend(label);
+                    line.extend(format_complex(re, im, self.display_mode, width));
+                }
+                OutputLine::Text(text) => {
+                    line.extend(text);
+                    line.truncate(SCREEN_WIDTH);
+                }
+            }
+            self.push_history(line);
+        }
+
+        // a table gets its own screen
+        if let Some(result) = self.engine.table.take() {
+            self.table = Some(Table::new(result, SCREEN_WIDTH));
+            self.overlay = Some(calc_ui::make_table_ui());
+        }
+    }
+
+    // puts what a program displayed in the history, then how it ended
+    fn show_program_result(&mut self, result: Result<RunStatus, &'static str>) {
+        self.show_output();
+        match result {
+            Ok(RunStatus::Finished) => (),
+            Ok(RunStatus::Prompt(name)) => {
+                let mut line = name;
+                line.extend_from_slice(b"?");
+                self.push_history(line);
+            }
+            Err(e) => {
+                let mut line = e.as_bytes().to_vec();
+                line.insert(0, b'>');
+                self.push_history(line);
+            }
+        }
+    }
+
+    fn set_display_mode(&mut self, display_mode: DisplayMode) {
+        self.display_mode = display_mode;
+        self.refresh_settings();
+    }
+
+    fn set_number_mode(&mut self, number_mode: NumberMode) {
+        self.engine.number_mode = number_mode;
+        self.refresh_settings();
+    }
+
+    fn set_uncertainty_mode(&mut self, uncertainty_mode: UncertaintyMode) {
+        self.engine.uncertainty_mode = uncertainty_mode;
+        self.refresh_settings();
+    }
+
+    // opens a page of the catalog, or moves to it if it's already open
+    fn show_catalog(&mut self, page: usize) {
+        let builtins = self.engine.builtins.all();
+        let pages = (builtins.len() + calc_ui::CATALOG_PAGE_SIZE - 1) / calc_ui::CATALOG_PAGE_SIZE;
+        let page = page.min(pages.saturating_sub(1));
+        let names: Vec<&[u8]> = builtins
+            .iter()
+            .skip(page * calc_ui::CATALOG_PAGE_SIZE)
+            .take(calc_ui::CATALOG_PAGE_SIZE)
+            .map(|builtin| builtin.name)
+            .collect();
+        let mut ui = calc_ui::make_catalog_ui(&names, page, pages);
+        if self.catalog.is_some() {
+            if let Some(overlay) = &self.overlay {
+                ui.cursor = overlay.cursor;
+            }
+        }
+        self.overlay = Some(ui);
+        self.catalog = Some(page);
+    }
+
+    // every program name in order, so a page of them can be picked by position
+    fn program_names(&self) -> Vec<Vec<u8>> {
+        let mut names: Vec<Vec<u8>> = self.engine.programs.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // opens the program screen on a page of existing programs, going back to the first after the last
+    fn show_programs(&mut self, page: usize) {
+        let names = self.program_names();
+        let pages = ((names.len() + calc_ui::PROGRAM_PAGE_SIZE - 1) / calc_ui::PROGRAM_PAGE_SIZE).max(1);
+        let page = page % pages;
+        let names: Vec<Vec<u8>> = names
+            .into_iter()
+            .skip(page * calc_ui::PROGRAM_PAGE_SIZE)
+            .take(calc_ui::PROGRAM_PAGE_SIZE)
+            .collect();
+        let mut ui = calc_ui::make_programs_ui(&names);
+        if self.programs.is_some() {
+            if let Some(overlay) = &self.overlay {
+                ui.cursor = overlay.cursor;
+            }
+        }
+        self.overlay = Some(ui);
+        self.programs = Some(page);
+    }
+
+    // opens a page of the memory screen, or moves to it if it's already open
+    fn show_memory(&mut self, page: usize) {
+        let saved = self.engine.saved();
+        let pages = ((saved.len() + calc_ui::MEMORY_PAGE_SIZE - 1) / calc_ui::MEMORY_PAGE_SIZE).max(1);
+        let page = page.min(pages - 1);
+        let names: Vec<Vec<u8>> = saved
+            .iter()
+            .skip(page * calc_ui::MEMORY_PAGE_SIZE)
+            .take(calc_ui::MEMORY_PAGE_SIZE)
+            .map(|entry| match entry {
+                Saved::Variable(name) => self.engine.symbols.name(*name).to_vec(),
+                Saved::Function(name) => {
+                    let mut label = self.engine.symbols.name(*name).to_vec();
+                    label.extend_from_slice(b"()");
+                    label
+                }
+            })
+            .collect();
+        let mut ui = calc_ui::make_memory_ui(&names, page, pages);
+        if self.memory.is_some() {
+            if let Some(overlay) = &self.overlay {
+                ui.cursor = overlay.cursor;
+            }
+        }
+        self.overlay = Some(ui);
+        self.memory = Some(page);
+    }
+
+    // rebuild the settings screen so it shows the new modes
+    fn refresh_settings(&mut self) {
+        if let Some(overlay) = &mut self.overlay {
+            let cursor = overlay.cursor;
+            *overlay = calc_ui::make_settings_ui(self.display_mode, self.engine.uncertainty_mode, self.engine.number_mode);
+            overlay.cursor = cursor;
+        }
+    }
 }
 
 pub trait CalculatorStuff {
@@ -217,7 +600,6 @@ impl CalculatorStuff for Calculator {
         tileset: &TileSet<'_>,
     ) {
         // draw bar that seperates keyboard and screen
-        const SEPERATOR_Y: u16 = 14;
         const SEPERATOR: [u16; 30] = [
             122, 124, 124, 123, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124,
             124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 124, 125,
@@ -260,6 +642,12 @@ impl CalculatorStuff for Calculator {
             );
         }
 
+        // clear BUSY from the bar
+        fg.print(vram, tileset, b"    ", &Vector2D::new(BUSY_X, SEPERATOR_Y));
+
+        // while editing a program or filling in a form its lines are shown instead of the history, newest at the bottom
+        let screen_lines = self.screen_lines();
+
         // draw history
         for i in 0..self.history.len() {
             fg.print(
@@ -268,10 +656,15 @@ impl CalculatorStuff for Calculator {
                 b"                              ",
                 &Vector2D::new(0, SEPERATOR_Y - 2 - (i as u16)),
             );
+            let line = match &screen_lines {
+                Some(lines) if i < lines.len() => &lines[lines.len() - 1 - i][..],
+                Some(_) => &b""[..],
+                None => &self.history[i][..],
+            };
             fg.print(
                 vram,
                 tileset,
-                &self.history[i],
+                line,
                 &Vector2D::new(0, SEPERATOR_Y - 2 - (i as u16)),
             );
         }
@@ -292,23 +685,58 @@ impl CalculatorStuff for Calculator {
     }
 
     fn handle_command(&mut self, command: Vec<u8>) {
+        let answer_cycle = self.answer_cycle.take();
+
         // have to covert to a string literal first because it just be like that sometime
         match String::from_utf8(command.clone()).unwrap().as_str() {
-            "cmd_enter" => {
-                self.history.rotate_right(2);
-                // the string the user answered
-                self.history[1] = self.user_input.clone();
-                // the answer
-                let equation_result = self.engine.eval(self.user_input.clone());
-                match equation_result {
-                    Ok(answer) => {
-                        self.history[0] = answer.to_string().as_bytes().to_vec();
+            "cmd_enter" if self.form.is_some() => {
+                // the input goes in the current field, an empty input keeps what was there
+                let form = self.form.as_mut().unwrap();
+                form.message = None;
+                if !self.user_input.is_empty() {
+                    form.values[form.field] = self.user_input.clone();
+                }
+                self.user_input.clear();
+                form.field += 1;
+                if form.field == form.values.len() {
+                    self.submit_form();
+                }
+            }
+            "cmd_enter" if self.editing.is_some() => {
+                // add the line to the program instead of running it
+                // if it doesn't fit, editing stops so the error can be seen, the lines before it are kept
+                let name = self.editing.clone().unwrap();
+                match self.engine.add_program_line(&name, self.user_input.clone()) {
+                    Ok(()) => {
+                        self.user_input.clear();
                     }
                     Err(e) => {
-                        self.history[0] = e.as_bytes().to_vec();
+                        self.editing = None;
+                        let mut line = e.as_bytes().to_vec();
+                        line.insert(0, b'>');
+                        self.push_history(line);
                     }
                 }
-                self.history[0].insert(0, b'>');
+            }
+            "cmd_enter" if self.engine.running.is_some() => {
+                // the input is the answer to the running program's prompt
+                self.push_history(self.user_input.clone());
+                let result = self.engine.answer_prompt(&self.user_input);
+                self.show_program_result(result);
+                self.user_input.clear();
+            }
+            "cmd_enter" => {
+                // the string the user answered
+                self.push_history(self.user_input.clone());
+                // the answer, after any extra lines like the roots from polyroots
+                let equation_result = self.engine.eval(self.user_input.clone());
+                self.show_output();
+                let mut line = match equation_result {
+                    Ok(answer) => self.format_answer(&answer, ANSWER_WIDTH),
+                    Err(e) => e.as_bytes().to_vec(),
+                };
+                line.insert(0, b'>');
+                self.push_history(line);
                 self.user_input.clear();
             }
             "cmd_set_angle_radians" => {
@@ -317,10 +745,197 @@ impl CalculatorStuff for Calculator {
             "cmd_set_angle_degrees" => {
                 self.engine.use_radians = false;
             }
+            "cmd_open_settings" => {
+                self.overlay = Some(calc_ui::make_settings_ui(self.display_mode, self.engine.uncertainty_mode, self.engine.number_mode));
+            }
+            "cmd_close_menu" => {
+                self.overlay = None;
+                self.catalog = None;
+                self.programs = None;
+                self.memory = None;
+                self.memory_selected = None;
+            }
+            "cmd_open_catalog" => {
+                self.show_catalog(0);
+            }
+            "cmd_catalog_prev" => {
+                self.show_catalog(self.catalog.unwrap_or(0).saturating_sub(1));
+            }
+            "cmd_catalog_next" => {
+                self.show_catalog(self.catalog.unwrap_or(0) + 1);
+            }
+            "cmd_open_memory" => {
+                self.memory_selected = None;
+                self.show_memory(0);
+            }
+            "cmd_memory_prev" => {
+                self.show_memory(self.memory.unwrap_or(0).saturating_sub(1));
+            }
+            "cmd_memory_next" => {
+                self.show_memory(self.memory.unwrap_or(0) + 1);
+            }
+            cmd if cmd.starts_with("cmd_memory_select_") => {
+                let index = (command[command.len() - 1] - b'0') as usize;
+                let page = self.memory.unwrap_or(0);
+                self.memory_selected = self.engine.saved().get(page * calc_ui::MEMORY_PAGE_SIZE + index).copied();
+            }
+            "cmd_memory_delete" => {
+                if let Some(Saved::Variable(name) | Saved::Function(name)) = self.memory_selected.take() {
+                    let _ = self.engine.delete(name);
+                    self.show_memory(self.memory.unwrap_or(0));
+                }
+            }
+            "cmd_open_programs" => {
+                self.programs = None;
+                self.show_programs(0);
+            }
+            "cmd_programs_more" => {
+                self.show_programs(self.programs.unwrap_or(0) + 1);
+            }
+            cmd if cmd.starts_with("cmd_pick_program_") => {
+                let index = (command[command.len() - 1] - b'0') as usize;
+                let page = self.programs.unwrap_or(0);
+                if let Some(name) = self.program_names().get(page * calc_ui::PROGRAM_PAGE_SIZE + index) {
+                    self.user_input.extend_from_slice(name);
+                }
+                self.overlay = None;
+                self.programs = None;
+            }
+            "cmd_close_table" => {
+                self.table = None;
+                self.overlay = None;
+            }
+            "cmd_table_up" | "cmd_table_down" | "cmd_table_page_up" | "cmd_table_page_down" | "cmd_table_top" | "cmd_table_end" => {
+                let height = self.history.len();
+                // a page is everything under the column names
+                let page = height as isize - 1;
+                if let Some(table) = &mut self.table {
+                    let amount = match command.as_slice() {
+                        b"cmd_table_up" => -1,
+                        b"cmd_table_down" => 1,
+                        b"cmd_table_page_up" => -page,
+                        b"cmd_table_page_down" => page,
+                        b"cmd_table_top" => -(table.row_count() as isize),
+                        _ => table.row_count() as isize,
+                    };
+                    table.scroll(amount, height);
+                }
+            }
+            "cmd_open_tvm" => {
+                self.editing = None;
+                self.form = Some(Form::tvm());
+                self.overlay = None;
+            }
+            "cmd_open_linear" => {
+                self.overlay = Some(calc_ui::make_linear_ui());
+            }
+            cmd if cmd.starts_with("cmd_linear_size_") => {
+                let size = (command[command.len() - 1] - b'0') as usize;
+                self.editing = None;
+                self.form = Some(Form::linear(size));
+                self.overlay = None;
+            }
+            "cmd_edit_program" => {
+                self.form = None;
+                let name = self.user_input.clone();
+                if name.first().map_or(true, |c| !c.is_ascii_alphabetic())
+                    || !name.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'_')
+                {
+                    self.push_history(b">type a program name first".to_vec());
+                } else {
+                    if !self.engine.programs.contains_key(&name) {
+                        self.engine.programs.insert(name.clone(), Program { lines: Vec::new() });
+                    }
+                    self.editing = Some(name);
+                    self.user_input.clear();
+                }
+                self.overlay = None;
+            }
+            "cmd_done_editing" => {
+                if let Some(name) = self.editing.take() {
+                    let mut line = b">saved ".to_vec();
+                    line.extend(name);
+                    self.push_history(line);
+                }
+                self.overlay = None;
+            }
+            "cmd_run_program" => {
+                self.editing = None;
+                self.overlay = None;
+                let name = self.user_input.clone();
+                let mut line = b"run ".to_vec();
+                line.extend_from_slice(&name);
+                self.push_history(line);
+                let result = self.engine.run_program(&name);
+                self.show_program_result(result);
+                self.user_input.clear();
+            }
+            "cmd_display_float" => {
+                self.set_display_mode(DisplayMode::Float);
+            }
+            "cmd_display_fix" => {
+                self.set_display_mode(DisplayMode::Fix(self.display_mode.digits()));
+            }
+            "cmd_display_sci" => {
+                self.set_display_mode(DisplayMode::Sci(self.display_mode.digits()));
+            }
+            "cmd_display_eng" => {
+                self.set_display_mode(DisplayMode::Eng(self.display_mode.digits()));
+            }
+            "cmd_number_precise" => {
+                self.set_number_mode(NumberMode::Precise);
+            }
+            "cmd_number_fast" => {
+                self.set_number_mode(NumberMode::Fast);
+            }
+            "cmd_uncertainty_gaussian" => {
+                self.set_uncertainty_mode(UncertaintyMode::Gaussian);
+            }
+            "cmd_uncertainty_interval" => {
+                self.set_uncertainty_mode(UncertaintyMode::Interval);
+            }
+            "cmd_prev_answer" => {
+                // if the last thing select put in is still there, swap it for the next older answer
+                let index = match answer_cycle {
+                    Some(index) if self.user_input.ends_with(&answer_name(index)) => {
+                        let len = self.user_input.len() - answer_name(index).len();
+                        self.user_input.truncate(len);
+                        (index + 1) % ANSWER_COUNT
+                    }
+                    _ => 0,
+                };
+                self.user_input.extend(answer_name(index));
+                self.answer_cycle = Some(index);
+            }
+            "cmd_open_answers" => {
+                let answers: Vec<Vec<u8>> = self
+                    .engine
+                    .prev_answers
+                    .iter()
+                    .map(|answer| self.format_answer(answer, PICKER_ANSWER_WIDTH))
+                    .collect();
+                self.overlay = Some(calc_ui::make_answers_ui(&answers));
+            }
+            cmd if cmd.starts_with("cmd_pick_answer_") => {
+                let index = (command[command.len() - 1] - b'0') as usize;
+                self.user_input.extend(answer_name(index));
+                self.overlay = None;
+            }
+            cmd if cmd.starts_with("cmd_display_digits_") => {
+                let digits = command[command.len() - 1] - b'0';
+                self.set_display_mode(self.display_mode.with_digits(digits));
+            }
             _ => {
                 for c in &command {
                     self.user_input.push(*c);
                 }
+                // typing something from a screen like the program one goes back to the keyboard
+                if !command.is_empty() {
+                    self.overlay = None;
+                    self.catalog = None;
+                    self.programs = None;
+                    self.memory = None;
+                }
             }
         }
     }
@@ -1,6 +0,0 @@
-pub mod core;
-pub mod error;
-pub mod math_function;
-pub mod solve;
-pub mod token;
-pub mod validate;
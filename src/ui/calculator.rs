@@ -1 +1,3 @@
 pub mod calc_ui;
+pub mod form;
+pub mod table;
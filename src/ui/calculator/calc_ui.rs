@@ -3,12 +3,15 @@ extern crate core;
 use crate::Vector2D;
 use alloc::*;
 
+use mathengine::format::DisplayMode;
+use mathengine::linear::{MAX_UNKNOWNS, MIN_UNKNOWNS};
+use mathengine::real::NumberMode;
+use mathengine::uncertainty::UncertaintyMode;
 use crate::ui::core::{Cell, CellType, Interface, BLANK, UI};
 
-// generates the calculators UI
-// should probably be a compile time macro but whatever it only runs once anyways
-pub fn make_ui() -> Interface {
-    let mut ui: Interface = Interface {
+// an empty interface the size of the keyboard area
+fn blank_ui() -> Interface {
+    Interface {
         cells: vec![
             vec![
                 Cell {
@@ -21,7 +24,13 @@ pub fn make_ui() -> Interface {
             30
         ],
         cursor: Vector2D::new(0, 0),
-    };
+    }
+}
+
+// generates the calculators UI
+// should probably be a compile time macro but whatever it only runs once anyways
+pub fn make_ui() -> Interface {
+    let mut ui: Interface = blank_ui();
 
     // number pad
     ui.add_manager((2, 3), (1, 1), b" ", b" ");
@@ -79,7 +88,7 @@ pub fn make_ui() -> Interface {
     ui.add_manager((10, 4), (1, 1), b"x", b"x");
     ui.add_manager((11, 4), (1, 1), b"y", b"y");
     ui.add_manager((12, 4), (1, 1), b"z", b"z");
-    ui.add_manager((12, 0), (1, 1), b" ", b" ");
+    ui.add_manager((12, 0), (1, 1), b";", b";");
     ui.add_manager((12, 1), (1, 1), b"(", b"(");
     ui.add_manager((12, 2), (1, 1), b")", b")");
     ui.add_manager((12, 3), (1, 1), b",", b",");
@@ -92,7 +101,7 @@ pub fn make_ui() -> Interface {
     ui.add_manager((19, 1), (4, 1), b"acos", b"acos(");
     ui.add_manager((19, 2), (4, 1), b"atan", b"atan(");
     ui.add_manager((14, 3), (4, 1), b"sqrt", b"sqrt(");
-    ui.add_manager((14, 4), (4, 1), b"dbug", b"sqr(x) = x * x");
+    ui.add_manager((14, 4), (4, 1), b"prgm", b"cmd_open_programs");
     ui.add_manager((19, 3), (4, 1), b"log", b"log(");
     ui.add_manager((19, 4), (4, 1), b"ln", b"ln(");
 
@@ -100,12 +109,204 @@ pub fn make_ui() -> Interface {
     ui.add_manager((24, 1), (3, 1), b"pi", b"pi");
     ui.add_manager((24, 2), (3, 1), b"e", b"e");
     ui.add_manager((24, 0), (3, 1), b"tau", b"tau");
-    ui.add_manager((27, 0), (3, 1), b"ans", b"ans");
+    ui.add_manager((27, 0), (3, 1), b"ans", b"cmd_open_answers");
+
+    // postfix operators ('%' shares its key with modulo)
+    ui.add_manager((27, 1), (3, 1), b"!", b"!");
+
+    // scientific notation, eg. 6.02E23
+    ui.add_manager((27, 2), (3, 1), b"E", b"E");
+
+    // late binding definition, eg. f(x) := a*x
+    ui.add_manager((27, 3), (3, 1), b":=", b":=");
 
     // settings bar
+    ui.add_manager((24, 3), (3, 1), b"set", b"cmd_open_settings");
     ui.add_manager((27, 4), (3, 1), b"RAD", b"cmd_set_angle_radians");
     ui.add_manager((24, 4), (3, 1), b"DEG", b"cmd_set_angle_degrees");
 
     // return the finished UI
     ui
 }
+
+// generates the settings screen, which replaces the keyboard while it is open
+pub fn make_settings_ui(display_mode: DisplayMode, uncertainty_mode: UncertaintyMode, number_mode: NumberMode) -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    // how answers are shown
+    ui.add_manager((0, 0), (5, 1), b"FLOAT", b"cmd_display_float");
+    ui.add_manager((6, 0), (5, 1), b"FIX", b"cmd_display_fix");
+    ui.add_manager((12, 0), (5, 1), b"SCI", b"cmd_display_sci");
+    ui.add_manager((18, 0), (5, 1), b"ENG", b"cmd_display_eng");
+
+    // precise f64 math, or fast fixed point math
+    ui.add_manager((0, 1), (7, 1), b"PRECISE", b"cmd_number_precise");
+    ui.add_manager((8, 1), (4, 1), b"FAST", b"cmd_number_fast");
+
+    // digits after the decimal point for FIX, SCI and ENG
+    for digit in 0..10u8 {
+        let action = format!("cmd_display_digits_{}", digit);
+        ui.add_manager((digit as u16 * 2, 2), (1, 1), &[b'0' + digit], action.as_bytes());
+    }
+
+    // how +/- is carried through, and a key to type it
+    ui.add_manager((0, 3), (5, 1), b"GAUSS", b"cmd_uncertainty_gaussian");
+    ui.add_manager((6, 3), (5, 1), b"INTVL", b"cmd_uncertainty_interval");
+    ui.add_manager((12, 3), (3, 1), b"+/-", b"+/-");
+
+    // current modes, just labels
+    ui.add_manager((0, 4), (10, 1), display_mode.name().as_bytes(), b"");
+    ui.add_manager((12, 4), (5, 1), uncertainty_mode.name().as_bytes(), b"");
+    ui.add_manager((18, 4), (7, 1), number_mode.name().as_bytes(), b"");
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// generates the previous answer picker, answers are the already formatted ans1..ans9
+pub fn make_answers_ui(answers: &[Vec<u8>]) -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    // 3 rows of 3, newest in the top left
+    for (i, answer) in answers.iter().enumerate() {
+        let mut name = format!("{}:", i + 1).into_bytes();
+        name.extend_from_slice(answer);
+        let action = format!("cmd_pick_answer_{}", i);
+        ui.add_manager(((i % 3) as u16 * 10, (i / 3) as u16), (9, 1), &name, action.as_bytes());
+    }
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// how many existing programs each page of the program screen has
+pub const PROGRAM_PAGE_SIZE: usize = 4;
+
+// generates the program screen, names are the existing programs on this page
+// anything that isn't a command gets typed in and closes the screen
+pub fn make_programs_ui(names: &[Vec<u8>]) -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    // statements
+    ui.add_manager((0, 0), (5, 1), b"for", b"for ");
+    ui.add_manager((6, 0), (5, 1), b"while", b"while ");
+    ui.add_manager((12, 0), (5, 1), b"if", b"if ");
+    ui.add_manager((18, 0), (5, 1), b"else", b"else");
+    ui.add_manager((24, 0), (5, 1), b"end", b"end");
+    ui.add_manager((0, 1), (5, 1), b"disp", b"disp ");
+    ui.add_manager((6, 1), (6, 1), b"prompt", b"prompt ");
+
+    // comparisons, true is 1 and false is 0
+    ui.add_manager((13, 1), (1, 1), b"<", b"<");
+    ui.add_manager((15, 1), (1, 1), b">", b">");
+    ui.add_manager((17, 1), (2, 1), b"<=", b"<=");
+    ui.add_manager((20, 1), (2, 1), b">=", b">=");
+    ui.add_manager((23, 1), (2, 1), b"==", b"==");
+    ui.add_manager((26, 1), (2, 1), b"!=", b"!=");
+
+    // the program name is whatever is on the input line
+    ui.add_manager((0, 2), (4, 1), b"edit", b"cmd_edit_program");
+    ui.add_manager((6, 2), (4, 1), b"done", b"cmd_done_editing");
+    ui.add_manager((12, 2), (4, 1), b"run", b"cmd_run_program");
+
+    // existing programs, pressing one types its name and more goes to the next page of them
+    // the action is the position, so a program can't be named like a command
+    for (i, name) in names.iter().take(PROGRAM_PAGE_SIZE).enumerate() {
+        let action = format!("cmd_pick_program_{}", i);
+        ui.add_manager((i as u16 * 6, 3), (5, 1), &name[..name.len().min(5)], action.as_bytes());
+    }
+    ui.add_manager((24, 3), (4, 1), b"more", b"cmd_programs_more");
+
+    // other solvers
+    ui.add_manager((0, 4), (6, 1), b"linear", b"cmd_open_linear");
+    ui.add_manager((7, 4), (3, 1), b"tvm", b"cmd_open_tvm");
+    ui.add_manager((11, 4), (7, 1), b"catalog", b"cmd_open_catalog");
+    ui.add_manager((19, 4), (5, 1), b"saved", b"cmd_open_memory");
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// generates the screen that picks how many unknowns the linear solver has
+pub fn make_linear_ui() -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    ui.add_manager((0, 0), (9, 1), b"unknowns:", b"");
+    for size in MIN_UNKNOWNS..=MAX_UNKNOWNS {
+        let action = format!("cmd_linear_size_{}", size);
+        ui.add_manager(((size - MIN_UNKNOWNS) as u16 * 2, 2), (1, 1), &[b'0' + size as u8], action.as_bytes());
+    }
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// how many built in functions each page of the catalog has
+pub const CATALOG_PAGE_SIZE: usize = 6;
+
+// generates a page of the catalog, names are the built in functions on it
+// pressing one types it in, and what each one does is drawn over the history
+pub fn make_catalog_ui(names: &[&[u8]], page: usize, pages: usize) -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    // 2 rows of 3
+    for (i, name) in names.iter().enumerate() {
+        let mut action = name.to_vec();
+        action.push(b'(');
+        ui.add_manager(((i % 3) as u16 * 10, (i / 3) as u16), (10, 1), name, &action);
+    }
+
+    ui.add_manager((0, 4), (4, 1), b"prev", b"cmd_catalog_prev");
+    ui.add_manager((5, 4), (4, 1), b"next", b"cmd_catalog_next");
+    let position = format!("{}/{}", page + 1, pages);
+    ui.add_manager((10, 4), (position.len() as u16, 1), position.as_bytes(), b"");
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// how many variables and functions each page of the memory screen has
+pub const MEMORY_PAGE_SIZE: usize = 6;
+
+// generates a page of the memory screen, names are the variables and functions on it
+// pressing one picks it and shows it with a '>' over the history, then del deletes it
+pub fn make_memory_ui(names: &[Vec<u8>], page: usize, pages: usize) -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    // 2 rows of 3
+    for (i, name) in names.iter().enumerate() {
+        let action = format!("cmd_memory_select_{}", i);
+        ui.add_manager(((i % 3) as u16 * 10, (i / 3) as u16), (9, 1), &name[..name.len().min(9)], action.as_bytes());
+    }
+
+    ui.add_manager((0, 4), (4, 1), b"prev", b"cmd_memory_prev");
+    ui.add_manager((5, 4), (4, 1), b"next", b"cmd_memory_next");
+    let position = format!("{}/{}", page + 1, pages);
+    ui.add_manager((10, 4), (position.len() as u16, 1), position.as_bytes(), b"");
+    ui.add_manager((17, 4), (3, 1), b"del", b"cmd_memory_delete");
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_menu");
+
+    ui
+}
+
+// generates the controls for scrolling through a table of results, the table itself is drawn over the history
+pub fn make_table_ui() -> Interface {
+    let mut ui: Interface = blank_ui();
+
+    ui.add_manager((0, 0), (4, 1), b"top", b"cmd_table_top");
+    ui.add_manager((5, 0), (4, 1), b"pgup", b"cmd_table_page_up");
+    ui.add_manager((10, 0), (4, 1), b"up", b"cmd_table_up");
+    ui.add_manager((0, 2), (4, 1), b"end", b"cmd_table_end");
+    ui.add_manager((5, 2), (4, 1), b"pgdn", b"cmd_table_page_down");
+    ui.add_manager((10, 2), (4, 1), b"down", b"cmd_table_down");
+
+    ui.add_manager((25, 4), (5, 1), b"back", b"cmd_close_table");
+
+    ui
+}
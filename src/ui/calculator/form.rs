@@ -0,0 +1,71 @@
+use alloc::{format, vec, vec::Vec};
+
+use mathengine::finance::TVM_FIELDS;
+
+// what a form is for, which decides what happens once every field is filled in
+#[derive(Clone, Copy, PartialEq)]
+pub enum FormKind {
+    // a system of linear equations with this many unknowns
+    Linear(usize),
+    // time value of money, the blank field gets solved
+    Tvm,
+}
+
+// a screen of named fields that get typed in one at a time on the input line
+pub struct Form {
+    pub kind: FormKind,
+    pub title: Vec<u8>,
+    pub labels: Vec<Vec<u8>>,
+    pub values: Vec<Vec<u8>>,
+    // the field enter puts the input line in
+    pub field: usize,
+    // shown instead of the title, eg. when the system has no solution
+    pub message: Option<Vec<u8>>,
+}
+
+impl Form {
+    fn new(kind: FormKind, title: &[u8], labels: Vec<Vec<u8>>) -> Form {
+        Form {
+            kind,
+            title: title.to_vec(),
+            values: vec![Vec::new(); labels.len()],
+            labels,
+            field: 0,
+            message: None,
+        }
+    }
+
+    // row r is a(r,1)*x1 + ... + a(r,n)*xn = b(r), fields go along each row
+    pub fn linear(size: usize) -> Form {
+        let mut labels: Vec<Vec<u8>> = Vec::new();
+        for row in 1..=size {
+            for column in 1..=size {
+                labels.push(format!("a{}{}", row, column).into_bytes());
+            }
+            labels.push(format!("b{}", row).into_bytes());
+        }
+        Form::new(FormKind::Linear(size), b"a11*x1+..+a1N*xN=b1, blank=0", labels)
+    }
+
+    pub fn tvm() -> Form {
+        let labels = TVM_FIELDS.iter().map(|name| name.as_bytes().to_vec()).collect();
+        Form::new(FormKind::Tvm, b"leave the one to solve blank", labels)
+    }
+
+    // the lines to draw, oldest first like the history, scrolled so the current field is always showing
+    pub fn lines(&self, height: usize) -> Vec<Vec<u8>> {
+        let mut lines: Vec<Vec<u8>> = vec![self.message.clone().unwrap_or(self.title.clone())];
+        for (i, (label, value)) in self.labels.iter().zip(&self.values).enumerate() {
+            let mut line = if i == self.field { b">".to_vec() } else { b" ".to_vec() };
+            line.extend_from_slice(label);
+            line.push(b'=');
+            line.extend_from_slice(value);
+            lines.push(line);
+        }
+
+        // the title is line 0, so the current field is line field + 1
+        let end = (self.field + 2).max(height).min(lines.len());
+        let start = end.saturating_sub(height);
+        lines[start..end].to_vec()
+    }
+}
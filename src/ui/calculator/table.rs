@@ -0,0 +1,55 @@
+use alloc::{vec, vec::Vec};
+
+use mathengine::core::ResultTable;
+
+// a screen of rows that can be scrolled through, like the (t, y) pairs from ode
+// only the rows on screen are turned into text, since a table can be hundreds of rows
+pub struct Table {
+    pub header: Vec<u8>,
+    pub result: ResultTable,
+    // the first row on screen
+    pub top: usize,
+    column_width: usize,
+}
+
+impl Table {
+    // every column gets the same share of the width
+    pub fn new(result: ResultTable, width: usize) -> Table {
+        let column_width = width / result.columns.len().max(1);
+        let mut header = Vec::new();
+        for name in &result.columns {
+            let start = header.len();
+            header.extend_from_slice(name.as_bytes());
+            header.resize(start + column_width, b' ');
+        }
+        Table { header, result, top: 0, column_width }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.result.rows().len()
+    }
+
+    // the lines to draw, oldest first like the history, format turns a number into text that fits in a width
+    pub fn lines<F>(&self, height: usize, format: F) -> Vec<Vec<u8>>
+    where
+        F: Fn(f64, usize) -> Vec<u8>,
+    {
+        let mut lines: Vec<Vec<u8>> = vec![self.header.clone()];
+        for row in self.result.rows().skip(self.top).take(height - 1) {
+            let mut line = Vec::new();
+            for n in row {
+                let start = line.len();
+                line.extend(format(*n, self.column_width - 1));
+                line.resize(start + self.column_width, b' ');
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    // moves by amount rows, without going past either end
+    pub fn scroll(&mut self, amount: isize, height: usize) {
+        let last = self.row_count().saturating_sub(height - 1);
+        self.top = (self.top as isize + amount).clamp(0, last as isize) as usize;
+    }
+}
@@ -165,6 +165,13 @@ impl UI for Interface {
         tileset: &TileSet<'_>,
         cursor: &mut Cursor,
     ) {
+        // clear old text, another interface may have been drawn here before
+        for y in 0..self.cells[0].len() {
+            for x in 0..self.cells.len() {
+                fg.print(vram, tileset, b" ", &Vector2D::from((x as u16 + pos.x, y as u16 + pos.y)));
+            }
+        }
+
         // loop over all UI tiles, and draw them. text is drawn on top
         for x in 0..self.cells.len() {
             for y in 0..self.cells[0].len() {
@@ -418,7 +425,7 @@ impl UI for Interface {
         }
         if input.is_just_pressed(Button::SELECT) {
             user_pressed_button = 2;
-            action = b"ans".to_vec();
+            action = b"cmd_prev_answer".to_vec();
         }
 
         (user_pressed_button, action)